@@ -0,0 +1,62 @@
+use crate::ErrorInEval;
+
+pub fn explain_eval_error(error: &ErrorInEval) {
+	match error {
+		ErrorInEval::NotYetImplemented { note } => {
+			println!("not yet implemented: {note}");
+		},
+		ErrorInEval::UndefinedName { name } => {
+			println!("name `{name}` is not defined");
+		},
+		ErrorInEval::MissingEntry { name } => {
+			println!("tuple has no entry named `{name}`");
+		},
+		ErrorInEval::MissingCaptureEntry { span, name, found } => {
+			match span {
+				Some(span) => println!("can't capture entry `{name}` at {}:{} from {found}", span.line, span.line_index),
+				None => println!("can't capture entry `{name}` from {found}")
+			}
+			println!("expected the tuple to have an entry named `{name}` - unnamed entries are named by their position");
+		},
+		ErrorInEval::DuplicateEntry { name } => {
+			println!("tuple already has an entry named `{name}`");
+			println!("expected entries spread into a tuple to have different names and positions to its other entries");
+		},
+		ErrorInEval::NotAFunction { name, found } => {
+			println!("`{name}` is {found}, which can't be evaluated as a function");
+		},
+		ErrorInEval::UnexpectedType { expected, found } => {
+			println!("unexpected {found} value");
+			println!("expected {expected}");
+		},
+		ErrorInEval::NotOfType { expected, found } => {
+			println!("unexpected {found} value");
+			println!("expected value of type {expected}");
+		},
+		ErrorInEval::BiOpOperands { bi_op, left, right } => {
+			println!("can't apply operator {bi_op:?} to {left} and {right}");
+		},
+		ErrorInEval::UnOpOperand { un_op, found } => {
+			println!("can't apply operator {un_op:?} to {found}");
+		},
+		ErrorInEval::IntegerOverflow => {
+			println!("integer overflowed");
+		},
+		ErrorInEval::DivideByZero => {
+			println!("integer division by zero");
+		},
+		ErrorInEval::NegativeExponent => {
+			println!("integers can't be raised to negative exponents");
+		},
+		ErrorInEval::ThrowOutsideCatch => {
+			println!("value thrown outside of a catch block or loop");
+		},
+		ErrorInEval::OutsideChain => {
+			println!("previous chain value used outside of a chain");
+		},
+		ErrorInEval::InFunction { name, inner } => {
+			explain_eval_error(inner);
+			println!("-> during evaluation of function {name}");
+		}
+	}
+}
\ No newline at end of file
@@ -0,0 +1,704 @@
+// Tree-walking evaluator.
+//
+// Evaluates parse trees directly, without any prior analysis. This favours a
+// readable implementation over speed - values and environments are cloned
+// freely.
+
+use std::rc::Rc;
+
+use wf_parse::{positional_index, BiOp, Bound, Parse, UnOp};
+use wf_token::{NumberLiteral, Span, Token, TokenType};
+
+pub mod explain;
+mod value;
+
+pub use value::{Closure, Tuple, Type, TypeDef, Value};
+
+#[derive(Debug, Clone)]
+pub enum ErrorInEval {
+	UndefinedName { name: String },
+	MissingEntry { name: String },
+	// A named entry was spread into a tuple which already had an entry of that name.
+	DuplicateEntry { name: String },
+	// Not every capture has a span - unnamed tuple captures don't have a name to point at.
+	MissingCaptureEntry { span: Option<Span>, name: String, found: String },
+	NotAFunction { name: String, found: &'static str },
+	UnexpectedType { expected: &'static str, found: &'static str },
+	NotOfType { expected: String, found: &'static str },
+	BiOpOperands { bi_op: BiOp, left: &'static str, right: &'static str },
+	UnOpOperand { un_op: UnOp, found: &'static str },
+	IntegerOverflow,
+	DivideByZero,
+	NegativeExponent,
+	ThrowOutsideCatch,
+	OutsideChain,
+	NotYetImplemented { note: &'static str },
+	InFunction { name: String, inner: Box<ErrorInEval> }
+}
+
+// Throws travel up the Rust call stack the same way errors do, until they reach the `catch` or `loop` they target.
+enum Unwind {
+	Throw { catch: usize, value: Value },
+	Error(ErrorInEval)
+}
+
+impl From<ErrorInEval> for Unwind {
+	fn from(error: ErrorInEval) -> Self {
+		Unwind::Error(error)
+	}
+}
+
+#[derive(Debug)]
+struct Binding {
+	name: String,
+	value: Value,
+	parent: Option<Rc<Binding>>
+}
+
+// Everything visible to an expression, decided lexically. Closures hold onto the environment they were defined in.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Env {
+	scope: Option<Rc<Binding>>,
+	catch: Option<usize>,
+	chain_previous: Option<Value>
+}
+
+impl Env {
+	fn bind(&mut self, name: String, value: Value) {
+		let parent = self.scope.take();
+		self.scope = Some(Rc::new(Binding { name, value, parent }));
+	}
+
+	fn lookup(&self, name: &str) -> Option<&Value> {
+		let mut binding = self.scope.as_deref();
+		while let Some(Binding { name: bound_name, value, parent }) = binding {
+			if bound_name == name { return Some(value); }
+			binding = parent.as_deref();
+		}
+		None
+	}
+
+	fn chain_previous(&self) -> Result<Value, ErrorInEval> {
+		self.chain_previous.clone().ok_or(ErrorInEval::OutsideChain)
+	}
+}
+
+#[derive(Default)]
+pub struct Evaluator {
+	globals: Env,
+	next_catch: usize
+}
+
+impl Evaluator {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn eval_let(&mut self, parse: &Parse) -> Result<(), ErrorInEval> {
+		let mut globals = self.globals.clone();
+		match self.bind_let(&mut globals, parse) {
+			Ok(()) => {
+				self.globals = globals;
+				Ok(())
+			},
+			// The catch this was thrown to has already finished evaluating.
+			Err(Unwind::Throw { .. }) => Err(ErrorInEval::ThrowOutsideCatch),
+			Err(Unwind::Error(error)) => Err(error)
+		}
+	}
+
+	pub fn lookup(&self, name: &str) -> Option<&Value> {
+		self.globals.lookup(name)
+	}
+
+	fn new_catch(&mut self) -> usize {
+		self.next_catch += 1;
+		self.next_catch
+	}
+
+	fn bind_let(&mut self, env: &mut Env, parse: &Parse) -> Result<(), Unwind> {
+		let Parse::Let { capture, expr, .. } = parse else { unreachable!("only lets can be bound") };
+		let value = match (capture.as_ref(), expr.as_ref()) {
+			(Parse::CaptureName { name, .. }, Parse::ValueFnDef { capture: fn_capture, expr: fn_expr }) => {
+				Value::Fn(Rc::new(Closure {
+					capture: fn_capture.as_ref().clone(),
+					expr: fn_expr.as_ref().clone(),
+					env: env.clone(),
+					self_name: Some(name.clone())
+				}))
+			},
+			(Parse::CaptureName { name, .. }, Parse::ValueTypeDef { ty }) => {
+				Value::Type(Type::Defined(Rc::new(TypeDef {
+					definition: ty.as_ref().clone(),
+					env: env.clone(),
+					self_name: Some(name.clone())
+				})))
+			},
+			_ => self.eval(env, expr)?
+		};
+		bind_capture(env, capture, value)?;
+		Ok(())
+	}
+
+	fn eval(&mut self, env: &Env, parse: &Parse) -> Result<Value, Unwind> {
+		let value = match parse {
+			Parse::Block { lets, expr } => {
+				let mut env = env.clone();
+				for parse in lets {
+					self.bind_let(&mut env, parse)?;
+				}
+				self.eval(&env, expr)?
+			},
+			Parse::ExprThrow { expr } => {
+				let value = self.eval(env, expr)?;
+				let catch = env.catch.ok_or(ErrorInEval::ThrowOutsideCatch)?;
+				return Err(Unwind::Throw { catch, value });
+			},
+			Parse::ExprChain { first, rest } => {
+				let mut value = self.eval(env, first)?;
+				for parse in rest {
+					let env = Env { chain_previous: Some(value), ..env.clone() };
+					value = self.eval(&env, parse)?;
+				}
+				value
+			},
+			Parse::ExprAutoChainFirstFnEval { name, datum, .. } => {
+				let mut tuple = Tuple { entries: vec![("0".to_string(), env.chain_previous()?)] };
+				if let Some(datum) = datum {
+					let Parse::ValueTuple { entries } = datum.as_ref() else { unreachable!("function data are always tuples") };
+					self.eval_tuple_entries(env, entries, 1, &mut tuple)?;
+				}
+				self.call(env, name, Value::tuple(tuple))?
+			},
+			Parse::ExprInfix { first, rest } => self.eval_infix(env, first, rest)?,
+			Parse::ExprRange { ascending, first, rest } => {
+				let mut previous = self.eval(env, first)?;
+				for (bound, _, operand) in rest {
+					let bi_op = match (ascending, bound) {
+						(true, Bound::Exclusive) => BiOp::Less,
+						(true, Bound::Inclusive) => BiOp::LessEqual,
+						(false, Bound::Exclusive) => BiOp::More,
+						(false, Bound::Inclusive) => BiOp::MoreEqual
+					};
+					let operand = self.eval(env, operand)?;
+					if !compare(&bi_op, &previous, &operand)? { return Ok(Value::Bool(false)); }
+					previous = operand;
+				}
+				Value::Bool(true)
+			},
+			Parse::ExprPrefix { un_ops, term } => {
+				let mut value = self.eval(env, term)?;
+				for (un_op, _) in un_ops.iter().rev() {
+					value = apply_un_op(un_op, value)?;
+				}
+				value
+			},
+			Parse::ExprAccess { accesses, term } => {
+				let mut value = self.eval(env, term)?;
+				for (name, _) in accesses {
+					// Instances can be accessed like the tuples they were created from.
+					if let Value::Instance { value: instance, .. } = value {
+						value = *instance;
+					}
+					let entry = value.as_tuple().get(name).cloned().ok_or_else(|| ErrorInEval::MissingEntry { name: name.clone() })?;
+					value = entry;
+				}
+				value
+			},
+
+			Parse::ValueFnEval { name, datum, .. } => {
+				let datum = self.eval(env, datum)?;
+				self.call(env, name, datum)?
+			},
+			Parse::ValueName { name, .. } => {
+				match name.as_str() {
+					"true" => Value::Bool(true),
+					"false" => Value::Bool(false),
+					name => env.lookup(name).cloned()
+						.or_else(|| Type::builtin(name).map(Value::Type))
+						.ok_or_else(|| ErrorInEval::UndefinedName { name: name.to_string() })?
+				}
+			},
+			Parse::ValueChainPrevious => env.chain_previous()?,
+			Parse::ValueConditional { branches, else_expr, .. } => {
+				for (if_expr, then_expr) in branches {
+					match self.eval(env, if_expr)? {
+						Value::Bool(true) => return self.eval(env, then_expr),
+						Value::Bool(false) => continue,
+						other => return Err(ErrorInEval::UnexpectedType { expected: "bool condition", found: other.external_name() }.into())
+					}
+				}
+				self.eval(env, else_expr)?
+			},
+			Parse::ValueTry { .. } => return Err(ErrorInEval::NotYetImplemented { note: "trying" }.into()),
+			Parse::ValueLoop { capture, initial_expr, body } => {
+				let catch = self.new_catch();
+				let mut value = self.eval(env, initial_expr)?;
+				loop {
+					let mut env = Env { catch: Some(catch), ..env.clone() };
+					bind_capture(&mut env, capture, value)?;
+					match self.eval(&env, body) {
+						Ok(next) => value = next,
+						Err(Unwind::Throw { catch: thrown_to, value }) if thrown_to == catch => break value,
+						Err(unwind) => return Err(unwind)
+					}
+				}
+			},
+			Parse::ValueBlock { catch, block } => {
+				if *catch {
+					let catch = self.new_catch();
+					let env = Env { catch: Some(catch), ..env.clone() };
+					match self.eval(&env, block) {
+						Err(Unwind::Throw { catch: thrown_to, value }) if thrown_to == catch => value,
+						result => result?
+					}
+				} else {
+					self.eval(env, block)?
+				}
+			},
+			Parse::ValueFnDef { capture, expr } => {
+				Value::Fn(Rc::new(Closure {
+					capture: capture.as_ref().clone(),
+					expr: expr.as_ref().clone(),
+					env: env.clone(),
+					self_name: None
+				}))
+			},
+			Parse::ValueNumber { number: NumberLiteral::Int { value, .. } } => Value::Int(*value),
+			Parse::ValueNumber { number: NumberLiteral::Num { value, .. } } => Value::Num(*value),
+			Parse::ValueTypeDef { ty } => Value::Type(Type::Defined(Rc::new(TypeDef {
+				definition: ty.as_ref().clone(),
+				env: env.clone(),
+				self_name: None
+			}))),
+			Parse::ValueNew { ty, value } => {
+				let ty = resolve_type(env, ty)?;
+				let value = self.eval(env, value)?;
+				match ty {
+					Type::Defined(ty) => {
+						check_type(&ty.env, &ty.definition, &value)?;
+						Value::Instance { ty, value: Box::new(value) }
+					},
+					// Builtin types have no definition to be distinct from.
+					ty => {
+						if !ty.accepts(&value) {
+							return Err(ErrorInEval::NotOfType { expected: ty.to_string(), found: value.external_name() }.into());
+						}
+						value
+					}
+				}
+			},
+			Parse::ValueView { .. } => return Err(ErrorInEval::NotYetImplemented { note: "views" }.into()),
+			Parse::ValueRequest { .. } | Parse::ValueProvide { .. } => {
+				return Err(ErrorInEval::NotYetImplemented { note: "requests and providers" }.into());
+			},
+			Parse::ValueString { string } => Value::Str(string.clone()),
+			Parse::ValueTuple { entries } => {
+				let mut tuple = Tuple::default();
+				self.eval_tuple_entries(env, entries, 0, &mut tuple)?;
+				Value::tuple(tuple)
+			},
+
+			Parse::Let { .. } | Parse::ValueTupleEntry { .. } => unreachable!("lets and tuple entries are not evaluated alone"),
+			Parse::CaptureName { .. } | Parse::CaptureTuple { .. } | Parse::CaptureTupleEntry { .. } | Parse::CaptureView { .. } => unreachable!("captures are not evaluated"),
+			Parse::TypeName { .. } | Parse::TypeTuple { .. } | Parse::TypeTupleEntry { .. } | Parse::TypeView { .. } => unreachable!("types are not evaluated")
+		};
+		Ok(value)
+	}
+
+	fn eval_tuple_entries(&mut self, env: &Env, entries: &[Parse], mut position: usize, tuple: &mut Tuple) -> Result<(), Unwind> {
+		for entry in entries {
+			let Parse::ValueTupleEntry { matcher, value } = entry else { unreachable!("tuples only contain tuple entries") };
+			let value = self.eval(env, value)?;
+			match matcher {
+				None => {
+					push_entry(tuple, position.to_string(), value)?;
+					position += 1;
+				},
+				Some(Token { ty: TokenType::Name { name }, .. }) => push_entry(tuple, name.clone(), value)?,
+				// Positional entries of the spread tuple are renumbered in order of position, carrying on from the positions
+				// before them, while named entries keep their names.
+				Some(_) => {
+					let mut entries = value.as_tuple().into_owned().entries;
+					let slots: Vec<usize> = (0..entries.len()).filter(|&slot| positional_index(&entries[slot].0).is_some()).collect();
+					let mut positional: Vec<(String, Value)> = slots.iter().map(|&slot| entries[slot].clone()).collect();
+					positional.sort_by_key(|(name, _)| positional_index(name));
+					for (slot, entry) in slots.into_iter().zip(positional) {
+						entries[slot] = entry;
+					}
+					for (name, value) in entries {
+						if positional_index(&name).is_some() {
+							push_entry(tuple, position.to_string(), value)?;
+							position += 1;
+						} else {
+							push_entry(tuple, name, value)?;
+						}
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+
+	fn call(&mut self, env: &Env, name: &str, datum: Value) -> Result<Value, Unwind> {
+		let closure = match env.lookup(name) {
+			Some(Value::Fn(closure)) => closure.clone(),
+			Some(other) => return Err(ErrorInEval::NotAFunction { name: name.to_string(), found: other.external_name() }.into()),
+			None => return Err(ErrorInEval::UndefinedName { name: name.to_string() }.into())
+		};
+		let mut env = closure.env.clone();
+		if let Some(self_name) = &closure.self_name {
+			env.bind(self_name.clone(), Value::Fn(closure.clone()));
+		}
+		let result = match bind_capture(&mut env, &closure.capture, datum) {
+			Ok(()) => self.eval(&env, &closure.expr),
+			Err(error) => Err(error.into())
+		};
+		result.map_err(|unwind| match unwind {
+			Unwind::Error(inner) => Unwind::Error(ErrorInEval::InFunction { name: name.to_string(), inner: Box::new(inner) }),
+			throw => throw
+		})
+	}
+
+	fn eval_infix(&mut self, env: &Env, first: &Parse, rest: &[(BiOp, Span, Parse)]) -> Result<Value, Unwind> {
+		let first = self.eval(env, first)?;
+		let Some((bi_op, _, _)) = rest.first() else { return Ok(first) };
+		match bi_op {
+			BiOp::Exponent => {
+				let mut operands = vec![first];
+				for (_, _, operand) in rest {
+					operands.push(self.eval(env, operand)?);
+				}
+				// Exponents are evaluated right-to-left.
+				let mut value = operands.pop().expect("operands start non-empty");
+				while let Some(base) = operands.pop() {
+					value = apply_bi_op(bi_op, base, value)?;
+				}
+				Ok(value)
+			},
+			BiOp::And | BiOp::Or => {
+				let short_circuit = matches!(bi_op, BiOp::Or);
+				let mut value = first;
+				let mut rest = rest.iter();
+				loop {
+					match value {
+						Value::Bool(bool) if bool == short_circuit => return Ok(Value::Bool(bool)),
+						Value::Bool(_) => (),
+						other => return Err(ErrorInEval::UnexpectedType { expected: "bool operand", found: other.external_name() }.into())
+					}
+					let Some((_, _, operand)) = rest.next() else { return Ok(Value::Bool(!short_circuit)) };
+					value = self.eval(env, operand)?;
+				}
+			},
+			BiOp::Equal | BiOp::NotEqual => {
+				// Equality comparisons form a chain; each operand is compared with its neighbour.
+				let mut previous = first;
+				for (bi_op, _, operand) in rest {
+					let operand = self.eval(env, operand)?;
+					if !compare(bi_op, &previous, &operand)? { return Ok(Value::Bool(false)); }
+					previous = operand;
+				}
+				Ok(Value::Bool(true))
+			},
+			_ => {
+				let mut value = first;
+				for (bi_op, _, operand) in rest {
+					let operand = self.eval(env, operand)?;
+					value = apply_bi_op(bi_op, value, operand)?;
+				}
+				Ok(value)
+			}
+		}
+	}
+}
+
+fn push_entry(tuple: &mut Tuple, name: String, value: Value) -> Result<(), ErrorInEval> {
+	if tuple.get(&name).is_some() {
+		return Err(ErrorInEval::DuplicateEntry { name });
+	}
+	tuple.entries.push((name, value));
+	Ok(())
+}
+
+fn bind_capture(env: &mut Env, capture: &Parse, value: Value) -> Result<(), ErrorInEval> {
+	match capture {
+		Parse::CaptureName { name, ty, .. } => {
+			if let Some(ty) = ty {
+				check_type(env, ty, &value)?;
+			}
+			env.bind(name.clone(), value)
+		},
+		Parse::CaptureTuple { entries, ty } => {
+			if let Some(ty) = ty {
+				check_type(env, ty, &value)?;
+			}
+			let tuple = value.as_tuple();
+			let mut position = 0;
+			let mut captured = vec![];
+			let mut rest = None;
+			for entry in entries {
+				let Parse::CaptureTupleEntry { matcher, capture, ty } = entry else { unreachable!("tuple captures only contain tuple capture entries") };
+				let name = match matcher {
+					Some(Token { ty: TokenType::Name { name }, .. }) => name.clone(),
+					// Captured once every other entry has been, so it knows what's left.
+					Some(_) => {
+						rest = capture.as_deref();
+						continue;
+					},
+					None => {
+						position += 1;
+						(position - 1).to_string()
+					}
+				};
+				let span = match (matcher, capture.as_deref()) {
+					(Some(matcher), _) => Some(matcher.span),
+					(None, Some(Parse::CaptureName { span, .. } | Parse::CaptureView { span, .. })) => Some(*span),
+					_ => None
+				};
+				let entry = tuple.get(&name).cloned().ok_or_else(|| ErrorInEval::MissingCaptureEntry { span, name: name.clone(), found: value.to_string() })?;
+				if let Some(ty) = ty {
+					check_type(env, ty, &entry)?;
+				}
+				match capture {
+					Some(capture) => bind_capture(env, capture, entry)?,
+					None => env.bind(name.clone(), entry)
+				}
+				captured.push(name);
+			}
+			// The rest of the tuple is captured as a tuple, with positional entries renumbered from the start.
+			// Like any other tuple, it's the same as its entry if only one positional entry is left.
+			if let Some(capture) = rest {
+				let mut remaining = Tuple::default();
+				let mut rest_position = 0;
+				for (name, entry) in &tuple.entries {
+					if captured.contains(name) {
+						continue;
+					}
+					if name.parse::<usize>().is_ok() {
+						remaining.entries.push((rest_position.to_string(), entry.clone()));
+						rest_position += 1;
+					} else {
+						remaining.entries.push((name.clone(), entry.clone()));
+					}
+				}
+				bind_capture(env, capture, Value::tuple(remaining))?;
+			}
+		},
+		Parse::CaptureView { .. } => return Err(ErrorInEval::NotYetImplemented { note: "views" }),
+		_ => unreachable!("captures are always names, tuples or views")
+	}
+	Ok(())
+}
+
+fn resolve_type(env: &Env, ty: &Parse) -> Result<Type, ErrorInEval> {
+	let Parse::TypeName { name, .. } = ty else { return Err(ErrorInEval::NotYetImplemented { note: "types other than names" }) };
+	match env.lookup(name) {
+		Some(Value::Type(ty)) => Ok(ty.clone()),
+		Some(value) => Err(ErrorInEval::UnexpectedType { expected: "type", found: value.external_name() }),
+		None => Type::builtin(name).ok_or_else(|| ErrorInEval::UndefinedName { name: name.clone() })
+	}
+}
+
+// Checks a value against a type or a capture with types, such as the definition of a type.
+fn check_type(env: &Env, ty: &Parse, value: &Value) -> Result<(), ErrorInEval> {
+	match ty {
+		Parse::TypeName { .. } => {
+			let ty = resolve_type(env, ty)?;
+			if !ty.accepts(value) {
+				return Err(ErrorInEval::NotOfType { expected: ty.to_string(), found: value.external_name() });
+			}
+		},
+		Parse::TypeTuple { entries, .. } => {
+			let tuple = value.as_tuple();
+			// Positional entries of the type always stand in for every position before `position`.
+			let mut position: usize = 0;
+			let mut checked = vec![];
+			let mut rest = None;
+			for entry in entries {
+				let Parse::TypeTupleEntry { matcher, count, ty } = entry else { unreachable!("tuple types only contain tuple type entries") };
+				match matcher {
+					Some(Token { ty: TokenType::Name { name }, .. }) => {
+						let value = tuple.get(name).ok_or_else(|| ErrorInEval::MissingEntry { name: name.clone() })?;
+						check_type(env, ty, value)?;
+						checked.push(name.clone());
+					},
+					Some(_) => rest = Some(ty),
+					// Counts can be far larger than any tuple, so only the entries the value has are looked at. Counts too
+					// large to add up can't be filled by any tuple, so they're left to fail as missing entries.
+					None => {
+						let count = count.map_or(1, |(_, count)| count);
+						let start = position;
+						position = position.saturating_add(count);
+						let mut found = vec![];
+						for (name, value) in &tuple.entries {
+							if let Some(index) = positional_index(name) && (start..position).contains(&index) {
+								check_type(env, ty, value)?;
+								found.push(index);
+							}
+						}
+						if found.len() < count {
+							let missing = (start..position).find(|index| !found.contains(index)).expect("fewer entries were found than counted");
+							return Err(ErrorInEval::MissingEntry { name: missing.to_string() });
+						}
+					}
+				}
+			}
+			if let Some(ty) = rest {
+				for (name, value) in &tuple.entries {
+					let counted = positional_index(name).is_some_and(|index| index < position);
+					if !counted && !checked.contains(name) {
+						check_type(env, ty, value)?;
+					}
+				}
+			}
+		},
+		Parse::TypeView { .. } => return Err(ErrorInEval::NotYetImplemented { note: "views" }),
+		Parse::CaptureName { ty, .. } | Parse::CaptureView { ty, .. } => {
+			if let Some(ty) = ty {
+				check_type(env, ty, value)?;
+			}
+		},
+		Parse::CaptureTuple { entries, ty } => {
+			if let Some(ty) = ty {
+				check_type(env, ty, value)?;
+			}
+			let tuple = value.as_tuple();
+			let mut position = 0;
+			for entry in entries {
+				let Parse::CaptureTupleEntry { matcher, capture, ty } = entry else { unreachable!("tuple captures only contain tuple capture entries") };
+				let name = match matcher {
+					Some(Token { ty: TokenType::Name { name }, .. }) => name.clone(),
+					// The rest of the tuple can be anything.
+					Some(_) => continue,
+					None => {
+						position += 1;
+						(position - 1).to_string()
+					}
+				};
+				let value = tuple.get(&name).ok_or(ErrorInEval::MissingEntry { name })?;
+				match (ty, capture) {
+					(Some(ty), _) => check_type(env, ty, value)?,
+					(None, Some(capture)) => check_type(env, capture, value)?,
+					(None, None) => {}
+				}
+			}
+		},
+		_ => unreachable!("types are always type names or captures")
+	}
+	Ok(())
+}
+
+fn apply_un_op(un_op: &UnOp, value: Value) -> Result<Value, ErrorInEval> {
+	let result = match (un_op, value) {
+		(UnOp::Not, Value::Bool(bool)) => Value::Bool(!bool),
+		(UnOp::Negate, Value::Int(int)) => Value::Int(int.checked_neg().ok_or(ErrorInEval::IntegerOverflow)?),
+		(UnOp::Negate, Value::Num(num)) => Value::Num(-num),
+		(UnOp::DoubleNegate, value @ (Value::Int(_) | Value::Num(_))) => value,
+		(UnOp::Count, Value::Str(string)) => Value::Int(string.len() as i64),
+		// Other single values are counted as a tuple holding only that value.
+		(UnOp::Count, value) => Value::Int(value.as_tuple().entries.len() as i64),
+		(un_op, value) => return Err(ErrorInEval::UnOpOperand { un_op: un_op.clone(), found: value.external_name() })
+	};
+	Ok(result)
+}
+
+fn apply_bi_op(bi_op: &BiOp, left: Value, right: Value) -> Result<Value, ErrorInEval> {
+	match (left, right) {
+		(Value::Int(left), Value::Int(right)) => apply_int_bi_op(bi_op, left, right),
+		(Value::Int(left), Value::Num(right)) => apply_num_bi_op(bi_op, left as f64, right),
+		(Value::Num(left), Value::Int(right)) => apply_num_bi_op(bi_op, left, right as f64),
+		(Value::Num(left), Value::Num(right)) => apply_num_bi_op(bi_op, left, right),
+		(left, right) => Err(ErrorInEval::BiOpOperands { bi_op: bi_op.clone(), left: left.external_name(), right: right.external_name() })
+	}
+}
+
+fn apply_int_bi_op(bi_op: &BiOp, left: i64, right: i64) -> Result<Value, ErrorInEval> {
+	if matches!(bi_op, BiOp::FloorDivide | BiOp::CeilDivide | BiOp::FloorMod) && right == 0 {
+		return Err(ErrorInEval::DivideByZero);
+	}
+	let result = match bi_op {
+		BiOp::Exponent => left.checked_pow(u32::try_from(right).map_err(|_| ErrorInEval::NegativeExponent)?),
+		BiOp::Multiply => left.checked_mul(right),
+		BiOp::Divide => return Ok(Value::Num(left as f64 / right as f64)),
+		BiOp::FloorDivide => left.checked_div(right).map(|quotient| {
+			if left % right != 0 && (left < 0) != (right < 0) { quotient - 1 } else { quotient }
+		}),
+		BiOp::CeilDivide => left.checked_div(right).map(|quotient| {
+			if left % right != 0 && (left < 0) == (right < 0) { quotient + 1 } else { quotient }
+		}),
+		BiOp::FloorMod => left.checked_rem(right).map(|remainder| {
+			if remainder != 0 && (remainder < 0) != (right < 0) { remainder + right } else { remainder }
+		}),
+		BiOp::Plus => left.checked_add(right),
+		BiOp::Minus => left.checked_sub(right),
+		_ => unreachable!("comparisons and boolean operators are not arithmetic")
+	};
+	result.map(Value::Int).ok_or(ErrorInEval::IntegerOverflow)
+}
+
+fn apply_num_bi_op(bi_op: &BiOp, left: f64, right: f64) -> Result<Value, ErrorInEval> {
+	let to_int = |num: f64| {
+		if right == 0.0 { Err(ErrorInEval::DivideByZero) }
+		else if num.is_finite() && num >= i64::MIN as f64 && num <= i64::MAX as f64 { Ok(Value::Int(num as i64)) }
+		else { Err(ErrorInEval::IntegerOverflow) }
+	};
+	let result = match bi_op {
+		BiOp::Exponent => left.powf(right),
+		BiOp::Multiply => left * right,
+		BiOp::Divide => left / right,
+		BiOp::FloorDivide => return to_int((left / right).floor()),
+		BiOp::CeilDivide => return to_int((left / right).ceil()),
+		BiOp::FloorMod => left - right * (left / right).floor(),
+		BiOp::Plus => left + right,
+		BiOp::Minus => left - right,
+		_ => unreachable!("comparisons and boolean operators are not arithmetic")
+	};
+	Ok(Value::Num(result))
+}
+
+fn compare(bi_op: &BiOp, left: &Value, right: &Value) -> Result<bool, ErrorInEval> {
+	if let BiOp::Equal | BiOp::NotEqual = bi_op {
+		return Ok(equals(left, right)? == matches!(bi_op, BiOp::Equal));
+	}
+	let ordering = match (left, right) {
+		(Value::Int(left), Value::Int(right)) => left.partial_cmp(right),
+		(Value::Int(left), Value::Num(right)) => (*left as f64).partial_cmp(right),
+		(Value::Num(left), Value::Int(right)) => left.partial_cmp(&(*right as f64)),
+		(Value::Num(left), Value::Num(right)) => left.partial_cmp(right),
+		(left, right) => return Err(ErrorInEval::BiOpOperands { bi_op: bi_op.clone(), left: left.external_name(), right: right.external_name() })
+	};
+	let Some(ordering) = ordering else { return Ok(false) };
+	Ok(match bi_op {
+		BiOp::Less => ordering.is_lt(),
+		BiOp::More => ordering.is_gt(),
+		BiOp::LessEqual => ordering.is_le(),
+		BiOp::MoreEqual => ordering.is_ge(),
+		_ => unreachable!("only ordering operators remain")
+	})
+}
+
+fn equals(left: &Value, right: &Value) -> Result<bool, ErrorInEval> {
+	let equal = match (left, right) {
+		(Value::Int(left), Value::Int(right)) => left == right,
+		(Value::Int(left), Value::Num(right)) => *left as f64 == *right,
+		(Value::Num(left), Value::Int(right)) => *left == *right as f64,
+		(Value::Num(left), Value::Num(right)) => left == right,
+		(Value::Bool(left), Value::Bool(right)) => left == right,
+		(Value::Str(left), Value::Str(right)) => left == right,
+		(Value::Tuple(left), Value::Tuple(right)) => {
+			if left.entries.len() != right.entries.len() { return Ok(false); }
+			for ((left_name, left), (right_name, right)) in left.entries.iter().zip(&right.entries) {
+				if left_name != right_name || !equals(left, right)? { return Ok(false); }
+			}
+			true
+		},
+		(Value::Type(Type::Builtin(left)), Value::Type(Type::Builtin(right))) => left == right,
+		(Value::Type(Type::Defined(left)), Value::Type(Type::Defined(right))) => Rc::ptr_eq(left, right),
+		(Value::Instance { ty: left_ty, value: left }, Value::Instance { ty: right_ty, value: right }) => {
+			Rc::ptr_eq(left_ty, right_ty) && equals(left, right)?
+		},
+		(Value::Fn(_), _) | (_, Value::Fn(_)) => return Err(ErrorInEval::UnexpectedType { expected: "comparable value", found: "function" }),
+		_ => false
+	};
+	Ok(equal)
+}
\ No newline at end of file
@@ -0,0 +1,160 @@
+use std::{borrow::Cow, fmt, rc::Rc};
+
+use wf_parse::Parse;
+
+use crate::Env;
+
+#[derive(Debug, Clone)]
+pub enum Value {
+	Int(i64),
+	Num(f64),
+	Bool(bool),
+	Str(String),
+	Tuple(Tuple),
+	Fn(Rc<Closure>),
+	Type(Type),
+	// A value created with `new`, which is only interchangeable with values of the same defined type.
+	Instance { ty: Rc<TypeDef>, value: Box<Value> }
+}
+
+impl Value {
+	pub fn unit() -> Self {
+		Value::Tuple(Tuple::default())
+	}
+
+	pub fn external_name(&self) -> &'static str {
+		match self {
+			Value::Int(_) => "int",
+			Value::Num(_) => "num",
+			Value::Bool(_) => "bool",
+			Value::Str(_) => "str",
+			Value::Tuple(_) => "tuple",
+			Value::Fn(_) => "function",
+			Value::Type(_) => "type",
+			Value::Instance { .. } => "instance"
+		}
+	}
+
+	// Tuples holding a single positional entry are the same as that entry, unless the entry is a tuple itself.
+	pub fn tuple(mut tuple: Tuple) -> Value {
+		if tuple.entries.len() == 1 && tuple.entries[0].0 == "0" && !matches!(tuple.entries[0].1, Value::Tuple(_)) {
+			tuple.entries.remove(0).1
+		} else {
+			Value::Tuple(tuple)
+		}
+	}
+
+	// A single value can be used as a tuple holding only that value.
+	pub fn as_tuple(&self) -> Cow<'_, Tuple> {
+		match self {
+			Value::Tuple(tuple) => Cow::Borrowed(tuple),
+			value => Cow::Owned(Tuple { entries: vec![("0".to_string(), value.clone())] })
+		}
+	}
+}
+
+// Entries are kept in the order they were written, alongside their name. Unnamed entries are named by their position.
+#[derive(Debug, Default, Clone)]
+pub struct Tuple {
+	pub entries: Vec<(String, Value)>
+}
+
+impl Tuple {
+	pub fn get(&self, name: &str) -> Option<&Value> {
+		self.entries.iter().find(|(entry_name, _)| entry_name == name).map(|(_, value)| value)
+	}
+}
+
+#[derive(Debug)]
+pub struct Closure {
+	pub(crate) capture: Parse,
+	pub(crate) expr: Parse,
+	pub(crate) env: Env,
+	// Set when the function was declared directly by a `let`, so that it can refer to itself recursively.
+	pub(crate) self_name: Option<String>
+}
+
+#[derive(Debug, Clone)]
+pub enum Type {
+	Builtin(&'static str),
+	Defined(Rc<TypeDef>)
+}
+
+impl Type {
+	pub fn builtin(name: &str) -> Option<Self> {
+		["int", "num", "bool", "str", "ty"].into_iter().find(|builtin| *builtin == name).map(Type::Builtin)
+	}
+
+	// Whether the value is of this type, without looking inside of tuples.
+	pub fn accepts(&self, value: &Value) -> bool {
+		match (self, value) {
+			(Type::Builtin("int"), Value::Int(_)) => true,
+			(Type::Builtin("num"), Value::Int(_) | Value::Num(_)) => true,
+			(Type::Builtin("bool"), Value::Bool(_)) => true,
+			(Type::Builtin("str"), Value::Str(_)) => true,
+			(Type::Builtin("ty"), Value::Type(_)) => true,
+			(Type::Defined(ty), Value::Instance { ty: instance_ty, .. }) => Rc::ptr_eq(ty, instance_ty),
+			_ => false
+		}
+	}
+}
+
+// Every evaluation of a `ty` defines a new type, distinct from all others.
+#[derive(Debug)]
+pub struct TypeDef {
+	pub(crate) definition: Parse,
+	pub(crate) env: Env,
+	// Set when the type was declared directly by a `let`, so that it can be named when printed.
+	pub(crate) self_name: Option<String>
+}
+
+impl fmt::Display for Type {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Type::Builtin(name) => write!(f, "{name}"),
+			Type::Defined(ty) => write!(f, "{}", ty.self_name.as_deref().unwrap_or("ty"))
+		}
+	}
+}
+
+impl fmt::Display for Value {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Value::Int(int) => write!(f, "{int}"),
+			Value::Num(num) if num.is_nan() => write!(f, "nan"),
+			Value::Num(num) if num.is_infinite() => write!(f, "{}inf", if *num < 0.0 { "-" } else { "" }),
+			Value::Num(num) => write!(f, "{num:?}"),
+			Value::Bool(bool) => write!(f, "{bool}"),
+			Value::Str(string) => {
+				write!(f, "\"")?;
+				for char in string.chars() {
+					match char {
+						'\n' => write!(f, "\\n")?,
+						'\t' => write!(f, "\\t")?,
+						'\\' => write!(f, "\\\\")?,
+						'"' => write!(f, "\\\"")?,
+						char => write!(f, "{char}")?
+					}
+				}
+				write!(f, "\"")
+			},
+			Value::Tuple(tuple) => {
+				write!(f, "[")?;
+				let mut position = 0;
+				for (index, (name, value)) in tuple.entries.iter().enumerate() {
+					if index > 0 { write!(f, ", ")?; }
+					if *name == position.to_string() {
+						position += 1;
+						write!(f, "{value}")?;
+					} else {
+						write!(f, ".{name} {value}")?;
+					}
+				}
+				write!(f, "]")
+			},
+			Value::Fn(_) => write!(f, "fn"),
+			Value::Type(ty) => write!(f, "{ty}"),
+			Value::Instance { ty, value } => write!(f, "new {} {value}", Type::Defined(ty.clone()))
+		}
+	}
+}
\ No newline at end of file
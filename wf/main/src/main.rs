@@ -1,7 +1,11 @@
-use std::{io::{self, BufReader, Read}, time::Instant};
+use std::{fs, io::{self, BufReader, Read}, path::PathBuf, time::Instant};
 
 use clap::{Parser, Subcommand};
+use wf_eval::explain::explain_eval_error;
 use wf_parse::explain::explain_parse_error;
+use wf_request::explain::explain_request_error;
+use wf_resolve::explain::explain_resolve_error;
+use wf_types::explain::{explain_lock_error, explain_type_error};
 
 #[derive(Parser)]
 #[command(version)]
@@ -23,7 +27,37 @@ enum Commands {
 	/// Tokenises and parses a sourcefile from stdin and emits a JSON syntax tree to stdout.
 	/// 
 	/// See `tokenise` for information about tokenisation.
-	Parse
+	Parse,
+
+	/// Tokenises and parses a source file from stdin, then checks it without
+	/// evaluating it.
+	/// 
+	/// Currently, this checks that every name is declared before it's used, that
+	/// types are used consistently, and that every request is provided.
+	Check {
+		/// A lockfile written by `lock`, which the inferred types of top-level
+		/// lets should still match.
+		#[arg(long)]
+		locked: Option<PathBuf>
+	},
+
+	/// Tokenises, parses and type checks a source file from stdin, and emits a
+	/// lockfile to stdout.
+	/// 
+	/// The lockfile lists the inferred type of every top-level let, and is meant
+	/// to be kept next to the source file so that `check --locked` can point out
+	/// when those types change.
+	Lock,
+
+	/// Tokenises, parses and evaluates a source file from stdin, and prints the
+	/// value of a top-level let to stdout.
+	/// 
+	/// Every top-level let is evaluated in order, so errors in any of them will
+	/// prevent the value from being printed.
+	Run {
+		/// The name captured by the top-level let to print.
+		name: String
+	}
 }
 
 fn main() {
@@ -31,7 +65,10 @@ fn main() {
 
 	match cli.command {
 		Commands::Tokenise => tokenise(),
-		Commands::Parse => parse()
+		Commands::Parse => parse(),
+		Commands::Check { locked } => check(locked),
+		Commands::Lock => lock(),
+		Commands::Run { name } => run(&name)
 	}
 }
 
@@ -65,4 +102,94 @@ fn parse() {
 	}
 	println!("Took {}micros to tokenise and {}micros to parse", time_to_tokenise.as_micros(), time_to_parse.as_micros())
 	
+}
+
+fn check(locked: Option<PathBuf>) {
+	let tokeniser = wf_token::Tokeniser::new(stdin_bytes!());
+	let parser = wf_parse::Parser::new(tokeniser);
+	let mut syntax = match parser.collect::<Result<Vec<_>, _>>() {
+		Ok(syntax) => syntax,
+		Err(err) => return explain_parse_error(&err)
+	};
+	for parse in &mut syntax {
+		wf_parse::normalise::normalise(parse);
+	}
+	let mut problems = false;
+	if let Err(errors) = wf_resolve::resolve(&syntax) {
+		problems = true;
+		for err in &errors {
+			explain_resolve_error(err);
+		}
+	}
+	if let Err(errors) = wf_types::check_types(&syntax) {
+		problems = true;
+		for err in &errors {
+			explain_type_error(err);
+		}
+	}
+	if let Err(errors) = wf_request::check_requests(&syntax) {
+		problems = true;
+		for err in &errors {
+			explain_request_error(err);
+		}
+	}
+	if let Some(path) = locked {
+		match fs::read_to_string(&path) {
+			Ok(lock) => if let Err(errors) = wf_types::lock::check_lock(&lock, &wf_types::infer_signatures(&syntax)) {
+				problems = true;
+				for err in &errors {
+					explain_lock_error(err);
+				}
+			},
+			Err(err) => {
+				problems = true;
+				println!("couldn't read lockfile at {}: {err}", path.display());
+			}
+		}
+	}
+	if !problems {
+		println!("no problems found");
+	}
+}
+
+fn lock() {
+	let tokeniser = wf_token::Tokeniser::new(stdin_bytes!());
+	let parser = wf_parse::Parser::new(tokeniser);
+	let mut syntax = match parser.collect::<Result<Vec<_>, _>>() {
+		Ok(syntax) => syntax,
+		Err(err) => return explain_parse_error(&err)
+	};
+	for parse in &mut syntax {
+		wf_parse::normalise::normalise(parse);
+	}
+	// Types that don't check would only lock in mistakes.
+	if let Err(errors) = wf_types::check_types(&syntax) {
+		for err in &errors {
+			explain_type_error(err);
+		}
+		return;
+	}
+	print!("{}", wf_types::lock::write_lock(&wf_types::infer_signatures(&syntax)));
+}
+
+fn run(name: &str) {
+	let tokeniser = wf_token::Tokeniser::new(stdin_bytes!());
+	let parser = wf_parse::Parser::new(tokeniser);
+	let mut syntax = match parser.collect::<Result<Vec<_>, _>>() {
+		Ok(syntax) => syntax,
+		Err(err) => return explain_parse_error(&err)
+	};
+	for parse in &mut syntax {
+		wf_parse::normalise::normalise(parse);
+	}
+	let mut evaluator = wf_eval::Evaluator::new();
+	for parse in &syntax {
+		if let Err(err) = evaluator.eval_let(parse) {
+			return explain_eval_error(&err);
+		}
+	}
+	match evaluator.lookup(name) {
+		Some(value) => println!("{value}"),
+		None => println!("no top-level let captures the name `{name}`")
+	}
 }
\ No newline at end of file
@@ -1,3 +1,5 @@
+use wf_token::TokenType;
+
 use crate::ErrorInParse;
 
 pub fn explain_parse_error(error: &ErrorInParse) {
@@ -13,6 +15,39 @@ pub fn explain_parse_error(error: &ErrorInParse) {
 			println!("end of file reached unexpectedly");
 			println!("expected {expected}");
 		},
+		ErrorInParse::MalformedToken { token } => match token.ty {
+			TokenType::Unterminated { kind } => {
+				println!("{} starting at {}:{} is never closed", kind.external_name(), token.span.line, token.span.line_index);
+				println!("expected closing delimiter before end of file");
+			},
+			TokenType::MalformedNumber { problem } | TokenType::MalformedString { problem } => {
+				println!("{} at {}:{}", token.ty.external_name(), token.span.line, token.span.line_index);
+				println!("{problem}");
+			},
+			_ => {
+				println!("{} at {}:{}", token.ty.external_name(), token.span.line, token.span.line_index);
+			}
+		},
+		ErrorInParse::MixedComparison { span, first, second } => {
+			println!("comparison {second:?} at {}:{} can't be mixed with {first:?}", span.line, span.line_index);
+			println!("expected comparisons to form an order, either all ascending or all descending");
+		},
+		ErrorInParse::ViewEscapesScope { span, name } => {
+			println!("view of `{name}` at {}:{} is passed out of the scope declaring `{name}`", span.line, span.line_index);
+			println!("expected `{name}` to be passed out alongside its view");
+		},
+		ErrorInParse::DuplicateEntryName { span, name } => {
+			println!("entry name `{name}` at {}:{} is used more than once in the same tuple", span.line, span.line_index);
+			println!("expected every entry to have a different name - unnamed entries are named by their position");
+		},
+		ErrorInParse::MultipleRests { span } => {
+			println!("rest of tuple at {}:{} is written more than once", span.line, span.line_index);
+			println!("expected at most one rest-of-tuple entry");
+		},
+		ErrorInParse::CountTooLarge { span } => {
+			println!("count at {}:{} gives the tuple more entries than can be numbered", span.line, span.line_index);
+			println!("expected a smaller count");
+		},
 		ErrorInParse::Context { start, name, inner } => {
 			explain_parse_error(inner);
 			if let Some(start) = start {
@@ -1,54 +1,112 @@
 use std::iter::Peekable;
 
 use serde::Serialize;
-use wf_token::{Span, Token, TokenType};
+use wf_token::{NumberLiteral, Span, Token, TokenType};
 
 pub mod explain;
+pub mod normalise;
 
 #[derive(Debug, Clone, Serialize)]
 pub enum BiOp { Exponent, Multiply, Divide, FloorDivide, CeilDivide, FloorMod, Plus, Minus, Equal, NotEqual, Less, More, LessEqual, MoreEqual, And, Or }
 
+impl BiOp {
+	// Higher priority operators are evaluated before lower priority operators.
+	pub fn priority(&self) -> u8 {
+		use BiOp::*;
+		match self {
+			Exponent => 6,
+			Multiply | Divide | FloorDivide | CeilDivide | FloorMod => 5,
+			Plus | Minus => 4,
+			Less | More | LessEqual | MoreEqual => 3,
+			Equal | NotEqual => 2,
+			And => 1,
+			Or => 0
+		}
+	}
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum Bound { Exclusive, Inclusive }
+
 #[derive(Debug, Clone, Serialize)]
 pub enum UnOp { Not, Negate, DoubleNegate, Count }
 
 #[derive(Debug, Clone, Serialize)]
 pub enum Parse {
 	Block { lets: Vec<Parse>, expr: Box<Parse> },
-	Let { capture: Box<Parse>, expr: Box<Parse> },
+	Let { span: Span, capture: Box<Parse>, expr: Box<Parse> },
 
 	ExprThrow { expr: Box<Parse> },
 	ExprChain { first: Box<Parse>, rest: Vec<Parse> },
-	ExprAutoChainFirstBiOp { bi_op: BiOp, operand: Box<Parse> },
-	ExprAutoChainFirstFnEval { name: String, datum: Option<Box<Parse>> },
-	ExprInfix { first: Box<Parse>, rest: Vec<(BiOp, Parse)> },
-	ExprPrefix { un_ops: Vec<UnOp>, term: Box<Parse> },
-	ExprAccess { accesses: Vec<String>, term: Box<Parse> },
-
-	ValueFnEval { name: String, datum: Box<Parse> },
-	ValueName { name: String },
-	ValueConditional { if_expr: Box<Parse>, then_expr: Box<Parse>, else_expr: Box<Parse> },
+	ExprAutoChainFirstFnEval { name: String, span: Span, datum: Option<Box<Parse>> },
+	// All operators in `rest` share the same priority. Higher priority operators are nested inside as operands.
+	// Operators, prefixes and accesses come with the span of their token, so that problems can point at them.
+	ExprInfix { first: Box<Parse>, rest: Vec<(BiOp, Span, Parse)> },
+	// Comparisons, which must all go in one direction - `<` and `<=` are ascending, while `>` and `>=` are descending.
+	ExprRange { ascending: bool, first: Box<Parse>, rest: Vec<(Bound, Span, Parse)> },
+	ExprPrefix { un_ops: Vec<(UnOp, Span)>, term: Box<Parse> },
+	ExprAccess { accesses: Vec<(String, Span)>, term: Box<Parse> },
+
+	ValueFnEval { name: String, span: Span, datum: Box<Parse> },
+	ValueName { span: Span, name: String },
+	ValueChainPrevious,
+	// Each branch is a condition and the expression used when it's the first condition to be true.
+	ValueConditional { span: Span, branches: Vec<(Parse, Parse)>, else_expr: Box<Parse> },
+	ValueTry { attempts: Vec<Parse>, fallback: Box<Parse> },
 	ValueLoop { capture: Box<Parse>, initial_expr: Box<Parse>, body: Box<Parse> },
 	ValueBlock { catch: bool, block: Box<Parse> },
 	ValueFnDef { capture: Box<Parse>, expr: Box<Parse> },
+	ValueTypeDef { ty: Box<Parse> },
+	ValueNew { ty: Box<Parse>, value: Box<Parse> },
+	ValueRequest { span: Span, ty: Box<Parse> },
+	ValueProvide { span: Span, value: Box<Parse>, block: Box<Parse> },
+	ValueNumber { number: NumberLiteral },
 	ValueString { string: String },
 	ValueTuple { entries: Vec<Parse> },
+	ValueView { span: Span, name: String },
 	ValueTupleEntry { matcher: Option<Token>, value: Box<Parse> },
 
-	CaptureName { name: String },
-	CaptureTuple { entries: Vec<Parse> },
+	CaptureName { span: Span, name: String, ty: Option<Box<Parse>> },
+	CaptureTuple { entries: Vec<Parse>, ty: Option<Box<Parse>> },
+	// Only entries without a capture, like `.name : str`, have a type here - otherwise, the type belongs to the capture.
 	CaptureTupleEntry { matcher: Option<Token>, capture: Option<Box<Parse>>, ty: Option<Box<Parse>> },
+	// Declares the viewed name again, in the scope it was viewed from.
+	CaptureView { span: Span, name: String, ty: Option<Box<Parse>> },
 
-	TypeName { name: String }
+	TypeName { span: Span, name: String },
+	TypeTuple { span: Span, entries: Vec<Parse> },
+	// Unnamed entries can be counted, as shorthand for writing the same type that many times.
+	TypeTupleEntry { matcher: Option<Token>, count: Option<(Span, usize)>, ty: Box<Parse> },
+	TypeView { span: Span, ty: Box<Parse> }
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub enum ErrorInParse {
 	UnexpectedToken { token: Token, expected: &'static str },
 	UnexpectedEndOfFile { expected: &'static str },
+	MalformedToken { token: Token },
 	NotYetImplemented { note: &'static str },
+	MixedComparison { span: Span, first: BiOp, second: BiOp },
+	ViewEscapesScope { span: Span, name: String },
+	DuplicateEntryName { span: Span, name: String },
+	MultipleRests { span: Span },
+	// Counted entries would take more positions than can be numbered.
+	CountTooLarge { span: Span },
 	Context { start: Option<Span>, name: &'static str, inner: Box<ErrorInParse> }
 }
 
+impl ErrorInParse {
+	// Tokens which are broken by themselves are reported as such, rather than as being out of place.
+	fn unexpected(token: Token, expected: &'static str) -> Self {
+		match token.ty {
+			TokenType::MalformedNumber { .. } | TokenType::MalformedString { .. } | TokenType::Unterminated { .. } => {
+				ErrorInParse::MalformedToken { token }
+			},
+			_ => ErrorInParse::UnexpectedToken { token, expected }
+		}
+	}
+}
+
 // FUTURE: use try {} block for this instead
 macro_rules! err_context {
 	($self:expr, $name:expr, $block:block) => {{
@@ -110,7 +168,7 @@ macro_rules! consume {
 			if matches!(token.ty, TokenType::$ty { .. }){ 
 				Ok(token) 
 			} else { 
-				Err(ErrorInParse::UnexpectedToken { token, expected: $expect })
+				Err(ErrorInParse::unexpected(token, $expect))
 			}
 		} else {
 			Err(ErrorInParse::UnexpectedEndOfFile { expected: $expect })
@@ -121,13 +179,187 @@ macro_rules! consume {
 macro_rules! expected {
 	($self:expr, $token:expr, $expect:expr) => {{
 		if let Some(token) = $token {
-			return Err(ErrorInParse::UnexpectedToken { token, expected: $expect })
+			return Err(ErrorInParse::unexpected(token, $expect))
 		} else {
 			return Err(ErrorInParse::UnexpectedEndOfFile { expected: $expect })
 		}		
 	}};
 }
 
+// Splits a flat infix operation at its lowest priority operators, nesting the higher priority operations between them.
+fn nest_by_priority(first: Parse, rest: Vec<(BiOp, Span, Parse)>) -> Result<Parse, ErrorInParse> {
+	let Some(lowest) = rest.iter().map(|(bi_op, _, _)| bi_op.priority()).min() else { return Ok(first) };
+	let mut bi_ops = vec![];
+	let mut groups = vec![(first, vec![])];
+	for (bi_op, span, operand) in rest {
+		if bi_op.priority() == lowest {
+			bi_ops.push((bi_op, span));
+			groups.push((operand, vec![]));
+		} else {
+			groups.last_mut().expect("groups start non-empty").1.push((bi_op, span, operand));
+		}
+	}
+	let mut operands = groups.into_iter().map(|(first, rest)| nest_by_priority(first, rest)).collect::<Result<Vec<_>, _>>()?.into_iter();
+	let first = Box::new(operands.next().expect("groups start non-empty"));
+	let rest = bi_ops.into_iter().zip(operands).map(|((bi_op, span), operand)| (bi_op, span, operand)).collect();
+	if lowest == BiOp::Less.priority() {
+		order_range(first, rest)
+	} else {
+		Ok(Parse::ExprInfix { first, rest })
+	}
+}
+
+// Comparisons must form an order, so they're gathered into a range going in one direction.
+fn order_range(first: Box<Parse>, rest: Vec<(BiOp, Span, Parse)>) -> Result<Parse, ErrorInParse> {
+	let first_bi_op = rest[0].0.clone();
+	let ascending = matches!(first_bi_op, BiOp::Less | BiOp::LessEqual);
+	let rest = rest.into_iter().map(|(bi_op, span, operand)| {
+		let bound = match (ascending, &bi_op) {
+			(true, BiOp::Less) | (false, BiOp::More) => Bound::Exclusive,
+			(true, BiOp::LessEqual) | (false, BiOp::MoreEqual) => Bound::Inclusive,
+			_ => return Err(ErrorInParse::MixedComparison { span, first: first_bi_op.clone(), second: bi_op })
+		};
+		Ok((bound, span, operand))
+	}).collect::<Result<_, _>>()?;
+	Ok(Parse::ExprRange { ascending, first, rest })
+}
+
+// Unnamed entries are named by their position, so they can collide with explicitly named entries like `.0`. Captures
+// and types can only have one entry for the rest of the tuple, but tuple values can spread in as many as they like.
+fn check_entry_names(entries: &[Parse]) -> Result<(), ErrorInParse> {
+	let mut names: Vec<(&str, Span)> = vec![];
+	// Unnamed entries take up every position before this one.
+	let mut position: usize = 0;
+	let mut rest = false;
+	for entry in entries {
+		let (matcher, count) = match entry {
+			Parse::ValueTupleEntry { matcher, .. } | Parse::CaptureTupleEntry { matcher, .. } => (matcher, None),
+			Parse::TypeTupleEntry { matcher, count, .. } => (matcher, *count),
+			_ => unreachable!("tuples only contain tuple entries")
+		};
+		match matcher {
+			Some(Token { ty: TokenType::Name { name }, span }) => {
+				let positional = positional_index(name).is_some_and(|index| index < position);
+				if positional || names.iter().any(|(first_name, _)| first_name == name) {
+					return Err(ErrorInParse::DuplicateEntryName { span: *span, name: name.clone() });
+				}
+				names.push((name, *span));
+			},
+			Some(Token { span, .. }) => {
+				if rest && !matches!(entry, Parse::ValueTupleEntry { .. }) {
+					return Err(ErrorInParse::MultipleRests { span: *span });
+				}
+				rest = true;
+			},
+			None => {
+				let start = position;
+				position = match count {
+					Some((span, count)) => position.checked_add(count).ok_or(ErrorInParse::CountTooLarge { span })?,
+					None => position + 1
+				};
+				let collision = names.iter().find(|(name, _)| positional_index(name).is_some_and(|index| (start..position).contains(&index)));
+				if let Some((name, span)) = collision {
+					return Err(ErrorInParse::DuplicateEntryName { span: *span, name: name.to_string() });
+				}
+			}
+		}
+	}
+	Ok(())
+}
+
+// Positional entries are named by their position, written without any leading zeroes.
+pub fn positional_index(name: &str) -> Option<usize> {
+	name.parse::<usize>().ok().filter(|index| index.to_string() == name)
+}
+
+// Views can't be passed out of the scope declaring the viewed name, unless the name is passed out alongside them.
+fn check_views_escaping(declared: &[&str], lets: &[Parse], expr: &Parse) -> Result<(), ErrorInParse> {
+	let mut views = vec![];
+	let mut names = vec![];
+	passed_out_of_block(lets, expr, &mut views, &mut names);
+	for (span, name) in views {
+		if declared.contains(&name) && !names.contains(&name) {
+			return Err(ErrorInParse::ViewEscapesScope { span, name: name.to_string() });
+		}
+	}
+	Ok(())
+}
+
+// Finds the views and names which might be part of the value of `expr`. Operators and function evaluations make new
+// values out of their operands, so they don't pass anything out. `previous` holds the earlier steps of the chain that
+// `expr` is the last step of, which `@` passes out.
+fn passed_out<'a>(expr: &'a Parse, previous: &[&'a Parse], views: &mut Vec<(Span, &'a str)>, names: &mut Vec<&'a str>) {
+	match expr {
+		Parse::ValueView { span, name } => views.push((*span, name)),
+		Parse::ValueName { name, .. } => names.push(name),
+		Parse::ValueChainPrevious => if let Some((last, previous)) = previous.split_last() {
+			passed_out(last, previous, views, names);
+		},
+		Parse::ValueTuple { entries } => {
+			for entry in entries {
+				if let Parse::ValueTupleEntry { value, .. } = entry {
+					passed_out(value, previous, views, names);
+				}
+			}
+		},
+		Parse::ExprChain { first, rest } => {
+			let steps: Vec<&Parse> = std::iter::once(first.as_ref()).chain(rest).collect();
+			let (last, previous) = steps.split_last().expect("chains always have a first step");
+			passed_out(last, previous, views, names);
+		},
+		// Only some of the entries are accessed, but which ones isn't followed.
+		Parse::ExprAccess { term, .. } => passed_out(term, previous, views, names),
+		Parse::ValueBlock { block, .. } => passed_out(block, previous, views, names),
+		Parse::Block { lets, expr } => passed_out_of_block(lets, expr, views, names),
+		Parse::ValueConditional { branches, else_expr, .. } => {
+			for (_, then_expr) in branches {
+				passed_out(then_expr, previous, views, names);
+			}
+			passed_out(else_expr, previous, views, names);
+		},
+		Parse::ValueTry { attempts, fallback } => {
+			for attempt in attempts {
+				passed_out(attempt, previous, views, names);
+			}
+			passed_out(fallback, previous, views, names);
+		},
+		Parse::ValueNew { value, .. } => passed_out(value, previous, views, names),
+		Parse::ValueProvide { block, .. } => passed_out(block, previous, views, names),
+		_ => {}
+	}
+}
+
+// Names bound by lets pass out whatever their expression does. Later lets can refer to earlier ones, so they're followed
+// from last to first. A name destructured from a tuple might hold any part of it, so the whole expression is followed.
+fn passed_out_of_block<'a>(lets: &'a [Parse], expr: &'a Parse, views: &mut Vec<(Span, &'a str)>, names: &mut Vec<&'a str>) {
+	passed_out(expr, &[], views, names);
+	for parse in lets.iter().rev() {
+		let Parse::Let { capture, expr, .. } = parse else { unreachable!("blocks only contain lets") };
+		let mut bound = vec![];
+		declared_names(capture, &mut bound);
+		if bound.iter().any(|name| names.contains(name)) {
+			passed_out(expr, &[], views, names);
+		}
+	}
+}
+
+fn declared_names<'a>(capture: &'a Parse, names: &mut Vec<&'a str>) {
+	match capture {
+		Parse::CaptureName { name, .. } => names.push(name),
+		Parse::CaptureTuple { entries, .. } => {
+			for entry in entries {
+				match entry {
+					Parse::CaptureTupleEntry { capture: Some(capture), .. } => declared_names(capture, names),
+					Parse::CaptureTupleEntry { matcher: Some(Token { ty: TokenType::Name { name }, .. }), capture: None, .. } => names.push(name),
+					_ => {}
+				}
+			}
+		},
+		// View captures declare a name in the scope it was viewed from, not this one.
+		_ => {}
+	}
+}
+
 pub struct Parser<Input: Iterator<Item = Token>> {
 	tokens: Peekable<Input>
 }
@@ -162,6 +394,13 @@ impl<Input: Iterator<Item = Token>> Parser<Input> {
 				gap!(self, unstoppable);
 				lets.push(self.parse_let_declaration()?);
 			}
+			let mut declared = vec![];
+			for parse in &lets {
+				if let Parse::Let { capture, .. } = parse {
+					declared_names(capture, &mut declared);
+				}
+			}
+			check_views_escaping(&declared, &lets, &expr)?;
 			Parse::Block { lets, expr: Box::new(expr) }
 		})
 	}
@@ -172,14 +411,14 @@ impl<Input: Iterator<Item = Token>> Parser<Input> {
 
 	fn parse_let_declaration(&mut self) -> Result<Parse, ErrorInParse> {
 		err_context!(self, "let declaration", {
-			consume!(self, Let, "let")?;
+			let span = consume!(self, Let, "let")?.span;
 			gap!(self, unstoppable);
 			let capture = Box::new(self.parse_capture()?);
 			gap!(self, unstoppable);
 			consume!(self, Equal, "assignment")?;
 			gap!(self, unstoppable);
 			let expr = self.parse_expr()?;
-			Parse::Let { capture, expr: Box::new(expr) }
+			Parse::Let { span, capture, expr: Box::new(expr) }
 		})
 	}
 
@@ -230,12 +469,12 @@ impl<Input: Iterator<Item = Token>> Parser<Input> {
 			if self.peek_bi_op().is_some() {
 				let mut rest = vec![];
 				while let Some(bi_op) = self.peek_bi_op() {
-					consume!(self, already_checked);
+					let span = consume!(self, already_checked).span;
 					gap!(self, unstoppable);
-					rest.push((bi_op, self.parse_expr_prefix()?));
+					rest.push((bi_op, span, self.parse_expr_prefix()?));
 					gap!(self, stop_at_line);
 				}
-				Parse::ExprInfix { first: Box::new(first), rest }
+				nest_by_priority(first, rest)?
 			} else {
 				first
 			}
@@ -244,29 +483,30 @@ impl<Input: Iterator<Item = Token>> Parser<Input> {
 
 	fn parse_expr_infix_auto(&mut self) -> Result<Parse, ErrorInParse> {
 		err_context!(self, "auto-chained infix operation", {
+			let mut rest = vec![];
 			let first = if let Some(bi_op) = self.peek_bi_op() {
-				consume!(self, already_checked);
+				let span = consume!(self, already_checked).span;
 				gap!(self, unstoppable);
-				Parse::ExprAutoChainFirstBiOp { bi_op, operand: Box::new(self.parse_expr_prefix()?) }
+				rest.push((bi_op, span, self.parse_expr_prefix()?));
+				Parse::ValueChainPrevious
 			} else {
 				match self.tokens.next() {
-					Some(Token { ty: TokenType::Name { name }, .. }) => {
+					Some(Token { ty: TokenType::Name { name }, span }) => {
 						gap!(self, stop_at_line);
 						let datum = if self.peek_value_tuple() { Some(Box::new(self.parse_value_tuple()?)) } else { None };
-						Parse::ExprAutoChainFirstFnEval { name, datum }
+						Parse::ExprAutoChainFirstFnEval { name, span, datum }
 					},
 					token => expected!(self, token, "infix operator or function evaluation")
 				}
 			};
 			gap!(self, stop_at_line);
-			let mut rest = vec![];
 			while let Some(bi_op) = self.peek_bi_op() {
-				consume!(self, already_checked);
+				let span = consume!(self, already_checked).span;
 				gap!(self, unstoppable);
-				rest.push((bi_op, self.parse_expr_prefix()?));
+				rest.push((bi_op, span, self.parse_expr_prefix()?));
 				gap!(self, stop_at_line);
 			}
-			Parse::ExprInfix { first: Box::new(first), rest }
+			nest_by_priority(first, rest)?
 		})
 	}
 
@@ -275,9 +515,9 @@ impl<Input: Iterator<Item = Token>> Parser<Input> {
 			if self.peek_un_op().is_some() {
 				let mut un_ops = vec![];
 				while let Some(un_op) = self.peek_un_op() {
-					consume!(self, already_checked);
+					let span = consume!(self, already_checked).span;
 					gap!(self, unstoppable);
-					un_ops.push(un_op);
+					un_ops.push((un_op, span));
 				}
 				Parse::ExprPrefix { un_ops, term: Box::new(self.parse_expr_access()?) }
 			} else {
@@ -295,9 +535,9 @@ impl<Input: Iterator<Item = Token>> Parser<Input> {
 				loop {
 					consume!(self, already_checked);
 					gap!(self, unstoppable);
-					match self.tokens.next() {
-						Some(Token { ty: TokenType::Name { name }, .. }) => accesses.push(name),
-						token => expected!(self, token, "name to be accessed")
+					match self.parse_entry_name("name to be accessed")? {
+						Token { ty: TokenType::Name { name }, span } => accesses.push((name, span)),
+						_ => unreachable!("entry names are always name tokens")
 					}
 					gap!(self, stop_at_line);
 					if !is_of_type!(self, Dot) {
@@ -311,6 +551,17 @@ impl<Input: Iterator<Item = Token>> Parser<Input> {
 		})
 	}
 
+	// Integers can be used as entry names, to refer to unnamed entries by position.
+	fn parse_entry_name(&mut self, expect: &'static str) -> Result<Token, ErrorInParse> {
+		match self.tokens.next() {
+			Some(token @ Token { ty: TokenType::Name { .. }, .. }) => Ok(token),
+			Some(Token { ty: TokenType::Number { value: NumberLiteral::Int { value, .. } }, span }) if value >= 0 => {
+				Ok(Token { ty: TokenType::Name { name: value.to_string() }, span })
+			},
+			token => expected!(self, token, expect)
+		}
+	}
+
 	fn peek_bi_op(&mut self) -> Option<BiOp> {
 		let token = self.tokens.peek()?;
 		let ty = match token.ty {
@@ -351,26 +602,40 @@ impl<Input: Iterator<Item = Token>> Parser<Input> {
 		err_context!(self, "value", {
 			if self.peek_value_tuple() {
 				self.parse_value_tuple()?
+			} else if self.peek_value_view() {
+				self.parse_value_view()?
 			} else if self.peek_value_conditional() {
 				self.parse_value_conditional()?
+			} else if self.peek_value_try() {
+				self.parse_value_try()?
 			} else if self.peek_value_loop() {
 				self.parse_value_loop()?
 			} else if self.peek_value_block() {
 				self.parse_value_block()?
 			} else if self.peek_value_fn_def() {
 				self.parse_value_fn_def()?
+			} else if self.peek_value_type_def() {
+				self.parse_value_type_def()?
+			} else if self.peek_value_new() {
+				self.parse_value_new()?
+			} else if self.peek_value_request() {
+				self.parse_value_request()?
+			} else if self.peek_value_provide() {
+				self.parse_value_provide()?
 			} else {
 				match self.tokens.next() {
-					Some(Token { ty: TokenType::Name { name }, .. }) => {
+					Some(Token { ty: TokenType::Name { name }, span }) => {
 						gap!(self, stop_at_line);
 						if self.peek_value_tuple() {
-							Parse::ValueFnEval { name, datum: Box::new(self.parse_value_tuple()?) }
+							Parse::ValueFnEval { name, span, datum: Box::new(self.parse_value_tuple()?) }
 						} else {
-							Parse::ValueName { name }
+							Parse::ValueName { span, name }
 						}
 					},
-					Some(Token { ty: TokenType::String { string }, .. }) => Parse::ValueString { string },
-					token => expected!(self, token, "function evaluation, name, string, tuple, conditional, loop, block, or function definition")
+					Some(Token { ty: TokenType::At, .. }) => Parse::ValueChainPrevious,
+					Some(Token { ty: TokenType::Number { value }, .. }) => Parse::ValueNumber { number: value },
+					Some(Token { ty: TokenType::String { string, .. }, .. }) => Parse::ValueString { string },
+					token => expected!(self, token, "function evaluation, name, previous chain value, number, string, tuple, view, conditional, try, loop, block, function definition, type definition, new value, request, or provider")
 				}
 			}
 		})
@@ -402,6 +667,7 @@ impl<Input: Iterator<Item = Token>> Parser<Input> {
 					expected!(self, self.tokens.next(), "closing bracket of tuple, or comma or new line to separate tuple entries");
 				}
 			}
+			check_entry_names(&entries)?;
 			Parse::ValueTuple { entries }
 		})
 	}
@@ -414,7 +680,7 @@ impl<Input: Iterator<Item = Token>> Parser<Input> {
 			} else if is_of_type!(self, Dot) {
 				consume!(self, already_checked);
 				gap!(self, unstoppable);
-				let name = consume!(self, Name, "name for tuple entry")?;
+				let name = self.parse_entry_name("name for tuple entry")?;
 				Some(name)
 			} else { None };
 			gap!(self, unstoppable);
@@ -423,24 +689,67 @@ impl<Input: Iterator<Item = Token>> Parser<Input> {
 		})
 	}
 
+	fn peek_value_view(&mut self) -> bool {
+		is_of_type!(self, OpenBrace)
+	}
+
+	fn parse_value_view(&mut self) -> Result<Parse, ErrorInParse> {
+		err_context!(self, "view", {
+			let span = consume!(self, OpenBrace, "opening brace of view")?.span;
+			gap!(self, unstoppable);
+			let name = match self.tokens.next() {
+				Some(Token { ty: TokenType::Name { name }, .. }) => name,
+				token => expected!(self, token, "name to view")
+			};
+			gap!(self, unstoppable);
+			consume!(self, CloseBrace, "closing brace of view")?;
+			Parse::ValueView { span, name }
+		})
+	}
+
 	fn peek_value_conditional(&mut self) -> bool {
 		is_of_type!(self, If)
 	}
 
 	fn parse_value_conditional(&mut self) -> Result<Parse, ErrorInParse> {
 		err_context!(self, "conditional value", {
-			consume!(self, If, "if")?;
-			gap!(self, unstoppable);
-			let if_expr = Box::new(self.parse_expr()?);
-			gap!(self, unstoppable);
-			consume!(self, Then, "then")?;
-			gap!(self, unstoppable);
-			let then_expr = Box::new(self.parse_expr()?);
-			gap!(self, unstoppable);
-			consume!(self, Else, "else")?;
+			let span = self.tokens.peek().map(|token| token.span).ok_or(ErrorInParse::UnexpectedEndOfFile { expected: "if" })?;
+			let mut branches = vec![];
+			while self.peek_value_conditional() {
+				consume!(self, already_checked);
+				gap!(self, unstoppable);
+				let if_expr = self.parse_expr()?;
+				gap!(self, unstoppable);
+				consume!(self, Then, "then")?;
+				gap!(self, unstoppable);
+				let then_expr = self.parse_expr()?;
+				gap!(self, unstoppable);
+				branches.push((if_expr, then_expr));
+			}
+			consume!(self, Else, "if or else")?;
 			gap!(self, unstoppable);
 			let else_expr = Box::new(self.parse_expr()?);
-			Parse::ValueConditional { if_expr, then_expr, else_expr }
+			Parse::ValueConditional { span, branches, else_expr }
+		})
+	}
+
+	fn peek_value_try(&mut self) -> bool {
+		is_of_type!(self, Try)
+	}
+
+	fn parse_value_try(&mut self) -> Result<Parse, ErrorInParse> {
+		err_context!(self, "try value", {
+			let mut attempts = vec![];
+			while self.peek_value_try() {
+				consume!(self, already_checked);
+				gap!(self, unstoppable);
+				attempts.push(self.parse_expr()?);
+				gap!(self, unstoppable);
+			}
+			consume!(self, Else, "try or else")?;
+			gap!(self, unstoppable);
+			let fallback = Box::new(self.parse_expr()?);
+			Parse::ValueTry { attempts, fallback }
 		})
 	}
 
@@ -492,23 +801,116 @@ impl<Input: Iterator<Item = Token>> Parser<Input> {
 			let capture = Box::new(self.parse_capture_tuple()?);
 			gap!(self, unstoppable);
 			let expr = Box::new(self.parse_expr()?);
+			let mut declared = vec![];
+			declared_names(&capture, &mut declared);
+			check_views_escaping(&declared, &[], &expr)?;
 			Parse::ValueFnDef { capture, expr }
 		})
 	}
 
+	fn peek_value_type_def(&mut self) -> bool {
+		is_of_type!(self, Ty)
+	}
+
+	fn parse_value_type_def(&mut self) -> Result<Parse, ErrorInParse> {
+		err_context!(self, "type definition", {
+			consume!(self, Ty, "ty")?;
+			gap!(self, unstoppable);
+			let ty = Box::new(self.parse_capture_or_type()?);
+			Parse::ValueTypeDef { ty }
+		})
+	}
+
+	fn peek_value_new(&mut self) -> bool {
+		is_of_type!(self, New)
+	}
+
+	fn parse_value_new(&mut self) -> Result<Parse, ErrorInParse> {
+		err_context!(self, "new value", {
+			consume!(self, New, "new")?;
+			gap!(self, unstoppable);
+			let ty = Box::new(self.parse_type()?);
+			gap!(self, unstoppable);
+			let value = Box::new(self.parse_expr()?);
+			Parse::ValueNew { ty, value }
+		})
+	}
+
+	fn peek_value_request(&mut self) -> bool {
+		is_of_type!(self, Req)
+	}
+
+	fn parse_value_request(&mut self) -> Result<Parse, ErrorInParse> {
+		err_context!(self, "request", {
+			let span = consume!(self, Req, "req")?.span;
+			gap!(self, unstoppable);
+			let ty = Box::new(self.parse_capture_or_type()?);
+			Parse::ValueRequest { span, ty }
+		})
+	}
+
+	fn peek_value_provide(&mut self) -> bool {
+		is_of_type!(self, Prov)
+	}
+
+	fn parse_value_provide(&mut self) -> Result<Parse, ErrorInParse> {
+		err_context!(self, "provider", {
+			let span = consume!(self, Prov, "prov")?.span;
+			gap!(self, unstoppable);
+			let value = Box::new(self.parse_expr()?);
+			gap!(self, unstoppable);
+			let block = Box::new(self.parse_block()?);
+			Parse::ValueProvide { span, value, block }
+		})
+	}
+
 	fn parse_capture(&mut self) -> Result<Parse, ErrorInParse> {
 		err_context!(self, "capture", {
-			if self.peek_capture_tuple() {
+			let capture = if self.peek_capture_tuple() {
 				self.parse_capture_tuple()?
+			} else if self.peek_capture_view() {
+				self.parse_capture_view()?
 			} else {
 				match self.tokens.next() {
-					Some(Token { ty: TokenType::Name { name }, .. }) => Parse::CaptureName { name },
-					token => expected!(self, token, "name capture or tuple capture")
+					Some(Token { ty: TokenType::Name { name }, span }) => Parse::CaptureName { span, name, ty: None },
+					token => expected!(self, token, "name capture, tuple capture or view capture")
 				}
+			};
+			gap!(self, stop_at_line);
+			if is_of_type!(self, Colon) {
+				consume!(self, already_checked);
+				gap!(self, unstoppable);
+				let ty = Some(Box::new(self.parse_type()?));
+				match capture {
+					Parse::CaptureName { span, name, .. } => Parse::CaptureName { span, name, ty },
+					Parse::CaptureTuple { entries, .. } => Parse::CaptureTuple { entries, ty },
+					Parse::CaptureView { span, name, .. } => Parse::CaptureView { span, name, ty },
+					_ => unreachable!("captures are always names, tuples or views")
+				}
+			} else {
+				capture
 			}
 		})
 	}
 
+	fn peek_capture_view(&mut self) -> bool {
+		is_of_type!(self, OpenBrace)
+	}
+
+	fn parse_capture_view(&mut self) -> Result<Parse, ErrorInParse> {
+		err_context!(self, "view capture", {
+			let span = consume!(self, OpenBrace, "opening brace of view capture")?.span;
+			gap!(self, unstoppable);
+			let name = match self.tokens.next() {
+				Some(Token { ty: TokenType::Name { name }, .. }) => name,
+				token => expected!(self, token, "name to declare through view")
+			};
+			gap!(self, unstoppable);
+			consume!(self, CloseBrace, "closing brace of view capture")?;
+			Parse::CaptureView { span, name, ty: None }
+		})
+	}
+
 	fn peek_capture_tuple(&mut self) -> bool {
 		is_of_type!(self, OpenBracket)
 	}
@@ -535,7 +937,8 @@ impl<Input: Iterator<Item = Token>> Parser<Input> {
 					expected!(self, self.tokens.next(), "closing bracket of tuple, or comma or new line to separate tuple entries");
 				}
 			}
-			Parse::CaptureTuple { entries }
+			check_entry_names(&entries)?;
+			Parse::CaptureTuple { entries, ty: None }
 		})
 	}
 
@@ -550,7 +953,7 @@ impl<Input: Iterator<Item = Token>> Parser<Input> {
 			} else if is_of_type!(self, Dot) {
 				consume!(self, already_checked);
 				gap!(self, unstoppable);
-				let name = consume!(self, Name, "name to access for tuple capture")?;
+				let name = self.parse_entry_name("name to access for tuple capture")?;
 				gap!(self, stop_at_line);
 				if is_of_type!(self, Comma) || is_of_type!(self, EndLine) || is_of_type!(self, CloseBracket) || is_of_type!(self, Colon) {
 					(Some(name), None)
@@ -573,14 +976,109 @@ impl<Input: Iterator<Item = Token>> Parser<Input> {
 		})
 	}
 
+	fn parse_capture_or_type(&mut self) -> Result<Parse, ErrorInParse> {
+		if self.peek_capture_tuple() {
+			self.parse_capture_tuple()
+		} else {
+			self.parse_type()
+		}
+	}
+
 	fn parse_type(&mut self) -> Result<Parse, ErrorInParse> {
 		err_context!(self, "type", {
+			if self.peek_type_tuple() {
+				self.parse_type_tuple()?
+			} else if self.peek_type_view() {
+				self.parse_type_view()?
+			} else {
+				self.parse_type_name()?
+			}
+		})
+	}
+
+	fn parse_type_name(&mut self) -> Result<Parse, ErrorInParse> {
+		err_context!(self, "type name", {
 			match self.tokens.next() {
-				Some(Token { ty: TokenType::Name { name }, .. }) => Parse::TypeName { name },
-				token => expected!(self, token, "capture type name")
+				Some(Token { ty: TokenType::Name { name }, span }) => Parse::TypeName { span, name },
+				// The type of type definitions.
+				Some(Token { ty: TokenType::Ty, span }) => Parse::TypeName { span, name: "ty".to_string() },
+				token => expected!(self, token, "type name, tuple type or view type")
 			}
 		})
 	}
+
+	fn peek_type_tuple(&mut self) -> bool {
+		is_of_type!(self, OpenBracket)
+	}
+
+	fn parse_type_tuple(&mut self) -> Result<Parse, ErrorInParse> {
+		err_context!(self, "tuple type", {
+			let span = consume!(self, OpenBracket, "opening bracket of tuple type")?.span;
+			gap!(self, unstoppable);
+			let mut entries = vec![];
+			loop {
+				if is_of_type!(self, CloseBracket) {
+					consume!(self, already_checked);
+					break;
+				}
+				entries.push(self.parse_type_tuple_entry()?);
+				gap!(self, stop_at_line);
+				if is_of_type!(self, CloseBracket) {
+					consume!(self, already_checked);
+					break;
+				} else if is_of_type!(self, Comma) || is_of_type!(self, EndLine) {
+					consume!(self, already_checked);
+					gap!(self, unstoppable);
+				} else {
+					expected!(self, self.tokens.next(), "closing bracket of tuple, or comma or new line to separate tuple entries");
+				}
+			}
+			check_entry_names(&entries)?;
+			Parse::TypeTuple { span, entries }
+		})
+	}
+
+	fn parse_type_tuple_entry(&mut self) -> Result<Parse, ErrorInParse> {
+		err_context!(self, "tuple type entry", {
+			let matcher = if is_of_type!(self, Ellipsis) {
+				let ellipsis = consume!(self, already_checked);
+				gap!(self, unstoppable);
+				Some(ellipsis)
+			} else if is_of_type!(self, Dot) {
+				consume!(self, already_checked);
+				gap!(self, unstoppable);
+				let name = self.parse_entry_name("name for tuple type entry")?;
+				gap!(self, unstoppable);
+				Some(name)
+			} else { None };
+			let count = match self.tokens.peek() {
+				Some(Token { ty: TokenType::Number { value: NumberLiteral::Int { value, .. } }, span }) if matcher.is_none() && *value >= 0 => {
+					let count = (*span, *value as usize);
+					consume!(self, already_checked);
+					gap!(self, unstoppable);
+					Some(count)
+				},
+				_ => None
+			};
+			let ty = Box::new(self.parse_type()?);
+			Parse::TypeTupleEntry { matcher, count, ty }
+		})
+	}
+
+	fn peek_type_view(&mut self) -> bool {
+		is_of_type!(self, OpenBrace)
+	}
+
+	fn parse_type_view(&mut self) -> Result<Parse, ErrorInParse> {
+		err_context!(self, "view type", {
+			let span = consume!(self, OpenBrace, "opening brace of view type")?.span;
+			gap!(self, unstoppable);
+			let ty = Box::new(self.parse_type()?);
+			gap!(self, unstoppable);
+			consume!(self, CloseBrace, "closing brace of view type")?;
+			Parse::TypeView { span, ty }
+		})
+	}
 }
 
 impl<Input: Iterator<Item = Token>> Iterator for Parser<Input> {
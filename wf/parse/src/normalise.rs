@@ -0,0 +1,138 @@
+// Single-value normalisation.
+//
+// A single value is interchangeable with a tuple or block holding only that
+// value, so `2`, `[2]`, `(2)` and `([2])` all normalise to `2`. Tuples and
+// blocks are only collapsed when nothing would be lost by doing so:
+// * tuples with one named entry, like `[.x 2]`, keep the name of the entry
+// * tuples spreading another tuple, like `[... rest]`, can hold any number of entries
+// * tuples holding one tuple, like `[[1, 2]]`, would lose a level of nesting -
+//   as might tuples holding a name like `[x]`, so only values that are never
+//   tuples are collapsed here, leaving the rest to evaluation
+// * blocks with lets, and catching blocks, do more than hold a value
+// * the datum of an auto-chained function evaluation still has `@` added to the front
+//
+// In turn, wherever a tuple is expected, a single value is treated as a tuple
+// holding only that value as its first positional entry.
+
+use std::mem;
+
+use crate::Parse;
+
+pub fn normalise(parse: &mut Parse) {
+	let collapsed = match parse {
+		Parse::Block { lets, expr } => {
+			for parse in lets.iter_mut() {
+				normalise(parse);
+			}
+			normalise(expr);
+			lets.is_empty().then(|| take(expr))
+		},
+		Parse::ValueBlock { catch, block } => {
+			normalise(block);
+			(!*catch && !matches!(block.as_ref(), Parse::Block { .. })).then(|| take(block))
+		},
+		Parse::ValueTuple { entries } => {
+			for entry in entries.iter_mut() {
+				normalise(entry);
+			}
+			match entries.as_mut_slice() {
+				[Parse::ValueTupleEntry { matcher: None, value }] if never_tuple(value) => Some(take(value)),
+				_ => None
+			}
+		},
+		Parse::ExprAutoChainFirstFnEval { datum, .. } => {
+			if let Some(Parse::ValueTuple { entries }) = datum.as_deref_mut() {
+				for entry in entries.iter_mut() {
+					normalise(entry);
+				}
+			}
+			None
+		},
+
+		Parse::Let { expr, .. } | Parse::ExprThrow { expr } | Parse::ValueFnDef { expr, .. } => {
+			normalise(expr);
+			None
+		},
+		Parse::ExprChain { first, rest } => {
+			normalise(first);
+			for parse in rest.iter_mut() {
+				normalise(parse);
+			}
+			None
+		},
+		Parse::ExprInfix { first, rest } => {
+			normalise(first);
+			for (_, _, parse) in rest.iter_mut() {
+				normalise(parse);
+			}
+			None
+		},
+		Parse::ExprRange { first, rest, .. } => {
+			normalise(first);
+			for (_, _, parse) in rest.iter_mut() {
+				normalise(parse);
+			}
+			None
+		},
+		Parse::ExprPrefix { term, .. } | Parse::ExprAccess { term, .. } => {
+			normalise(term);
+			None
+		},
+		Parse::ValueFnEval { datum, .. } => {
+			normalise(datum);
+			None
+		},
+		Parse::ValueConditional { branches, else_expr, .. } => {
+			for (if_expr, then_expr) in branches.iter_mut() {
+				normalise(if_expr);
+				normalise(then_expr);
+			}
+			normalise(else_expr);
+			None
+		},
+		Parse::ValueTry { attempts, fallback } => {
+			for parse in attempts.iter_mut() {
+				normalise(parse);
+			}
+			normalise(fallback);
+			None
+		},
+		Parse::ValueLoop { initial_expr, body, .. } => {
+			normalise(initial_expr);
+			normalise(body);
+			None
+		},
+		Parse::ValueNew { value, .. } | Parse::ValueTupleEntry { value, .. } => {
+			normalise(value);
+			None
+		},
+		Parse::ValueProvide { value, block, .. } => {
+			normalise(value);
+			normalise(block);
+			None
+		},
+
+		// Captures and types describe tuples rather than hold values, so they're left as written.
+		Parse::ValueName { .. } | Parse::ValueChainPrevious | Parse::ValueTypeDef { .. } | Parse::ValueRequest { .. } | Parse::ValueNumber { .. } | Parse::ValueString { .. } | Parse::ValueView { .. } => None,
+		Parse::CaptureName { .. } | Parse::CaptureTuple { .. } | Parse::CaptureTupleEntry { .. } | Parse::CaptureView { .. } => None,
+		Parse::TypeName { .. } | Parse::TypeTuple { .. } | Parse::TypeTupleEntry { .. } | Parse::TypeView { .. } => None
+	};
+	if let Some(collapsed) = collapsed {
+		*parse = collapsed;
+	}
+}
+
+// Going by how the value is written, as names and evaluations can hold tuples.
+fn never_tuple(parse: &Parse) -> bool {
+	match parse {
+		Parse::ValueName { name, .. } => name == "true" || name == "false",
+		Parse::ValueNumber { .. } | Parse::ValueString { .. } | Parse::ValueFnDef { .. } | Parse::ValueTypeDef { .. } | Parse::ValueNew { .. } => true,
+		// Operators never give tuples.
+		Parse::ExprInfix { .. } | Parse::ExprRange { .. } | Parse::ExprPrefix { .. } => true,
+		_ => false
+	}
+}
+
+fn take(parse: &mut Parse) -> Parse {
+	mem::replace(parse, Parse::ValueChainPrevious)
+}
\ No newline at end of file
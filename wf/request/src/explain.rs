@@ -0,0 +1,21 @@
+use crate::ErrorInRequests;
+
+pub fn explain_request_error(error: &ErrorInRequests) {
+	match error {
+		ErrorInRequests::Unprovided { request, ty, caller } => {
+			println!("request for {ty} at {}:{} is never provided", request.line, request.line_index);
+			if let Some(caller) = caller {
+				println!("-> passed on to function evaluation at {}:{}", caller.line, caller.line_index);
+			}
+			println!("expected a provider of {ty} around the request or a function evaluation passing it on");
+		},
+		ErrorInRequests::Unchecked { request, ty, through } => {
+			println!("request for {ty} at {}:{} can't be checked", request.line, request.line_index);
+			match through {
+				Some(through) => println!("-> passed on to a function used as a value at {}:{}", through.line, through.line_index),
+				None => println!("-> passed on to an anonymous function")
+			}
+			println!("expected a provider of {ty} around the request, or the function passing it on to only be evaluated by name");
+		}
+	}
+}
\ No newline at end of file
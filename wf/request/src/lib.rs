@@ -0,0 +1,322 @@
+// Static matching of requests to providers.
+//
+// Every `req` is matched to the most nested `prov` that satisfies it. Requests
+// that aren't provided inside a function are passed on to every caller of that
+// function, so they're only reported once they reach code outside of any
+// function. Functions used as values can be called from anywhere, so requests
+// passed on to them are reported as unable to be checked. The types of requests
+// and providers come from the type checker.
+
+use std::{collections::HashMap, mem};
+
+use wf_parse::Parse;
+use wf_token::{Span, Token, TokenType};
+use wf_types::Type;
+
+pub mod explain;
+
+#[derive(Debug, Clone)]
+pub enum ErrorInRequests {
+	// `caller` is the outermost function evaluation that the request was passed on through, if any.
+	Unprovided { request: Span, ty: Type, caller: Option<Span> },
+	// `through` is where the function the request was passed on to is used as a value. Anonymous functions are always
+	// used as values, but have no name to point at.
+	Unchecked { request: Span, ty: Type, through: Option<Span> }
+}
+
+impl ErrorInRequests {
+	fn span(&self) -> Span {
+		match self {
+			ErrorInRequests::Unprovided { request, caller, .. } => caller.unwrap_or(*request),
+			ErrorInRequests::Unchecked { request, through, .. } => through.unwrap_or(*request)
+		}
+	}
+}
+
+struct Request {
+	span: Span,
+	ty: Type
+}
+
+impl Request {
+	// Providers whose values can't be worked out might provide anything, so they aren't ruled out.
+	fn provided_by(&self, providers: &[Type]) -> bool {
+		providers.iter().rev().any(|provider| self.ty.accepts(provider))
+	}
+}
+
+struct Call {
+	callee: usize,
+	span: Span,
+	// The types provided around the call inside the calling function, innermost last.
+	providers: Vec<Type>
+}
+
+impl Call {
+	fn provides(&self, request: &Request) -> bool {
+		request.provided_by(&self.providers)
+	}
+}
+
+#[derive(Default)]
+struct Function {
+	// Requests written in the function which aren't provided inside of it.
+	requests: Vec<usize>,
+	calls: Vec<Call>
+}
+
+#[derive(Default)]
+struct Analysis {
+	// From the type checker, by the index of each `req` or `prov` keyword.
+	exchanged: HashMap<usize, Type>,
+	requests: Vec<Request>,
+	functions: Vec<Function>,
+	// Each name, and the function it's bound to if it's known to be one.
+	scope: Vec<(String, Option<usize>)>,
+	// Innermost last. Function bodies start without providers, as they're provided to by their callers instead.
+	providers: Vec<Type>,
+	// Not set outside of any function.
+	function: Option<usize>,
+	outermost_calls: Vec<Call>,
+	// Functions used as values, rather than evaluated by name, and where they're used.
+	escapes: Vec<(usize, Option<Span>)>,
+	errors: Vec<ErrorInRequests>
+}
+
+pub fn check_requests(syntax: &[Parse]) -> Result<(), Vec<ErrorInRequests>> {
+	let mut analysis = Analysis { exchanged: wf_types::infer_exchanged_types(syntax), ..Analysis::default() };
+	for parse in syntax {
+		analysis.visit_let(parse);
+	}
+	analysis.finish()
+}
+
+impl Analysis {
+	fn visit_let(&mut self, parse: &Parse) {
+		let Parse::Let { capture, expr, .. } = parse else { unreachable!("only lets can be bound") };
+		match (capture.as_ref(), expr.as_ref()) {
+			// Declared before the body is visited, so that the function can call itself.
+			(Parse::CaptureName { name, .. }, Parse::ValueFnDef { capture: fn_capture, expr: fn_expr }) => {
+				let function = self.new_function();
+				self.scope.push((name.clone(), Some(function)));
+				self.visit_fn(function, fn_capture, fn_expr);
+			},
+			// Calls through another name for a function pass on the same requests.
+			(Parse::CaptureName { name, .. }, Parse::ValueName { name: aliased, .. }) if let Some(function) = self.function_named(aliased) => {
+				self.scope.push((name.clone(), Some(function)));
+			},
+			(capture, expr) => {
+				self.visit(expr);
+				self.bind_capture(capture);
+			}
+		}
+	}
+
+	fn new_function(&mut self) -> usize {
+		self.functions.push(Function::default());
+		self.functions.len() - 1
+	}
+
+	fn visit_fn(&mut self, function: usize, capture: &Parse, expr: &Parse) {
+		let scope_len = self.scope.len();
+		let providers = mem::take(&mut self.providers);
+		let outer_function = self.function.replace(function);
+		self.bind_capture(capture);
+		self.visit(expr);
+		self.function = outer_function;
+		self.providers = providers;
+		self.scope.truncate(scope_len);
+	}
+
+	fn visit(&mut self, parse: &Parse) {
+		match parse {
+			Parse::Block { lets, expr } => {
+				let scope_len = self.scope.len();
+				for parse in lets {
+					self.visit_let(parse);
+				}
+				self.visit(expr);
+				self.scope.truncate(scope_len);
+			},
+			Parse::ExprThrow { expr } => self.visit(expr),
+			Parse::ExprChain { first, rest } => {
+				self.visit(first);
+				for parse in rest {
+					self.visit(parse);
+				}
+			},
+			Parse::ExprAutoChainFirstFnEval { name, span, datum } => {
+				if let Some(datum) = datum {
+					self.visit(datum);
+				}
+				self.call(name, *span);
+			},
+			Parse::ExprInfix { first, rest } => {
+				self.visit(first);
+				for (_, _, parse) in rest {
+					self.visit(parse);
+				}
+			},
+			Parse::ExprRange { first, rest, .. } => {
+				self.visit(first);
+				for (_, _, parse) in rest {
+					self.visit(parse);
+				}
+			},
+			Parse::ExprPrefix { term, .. } | Parse::ExprAccess { term, .. } => self.visit(term),
+			Parse::ValueFnEval { name, span, datum } => {
+				self.visit(datum);
+				self.call(name, *span);
+			},
+			Parse::ValueName { span, name } | Parse::ValueView { span, name } => {
+				if let Some(function) = self.function_named(name) {
+					self.escapes.push((function, Some(*span)));
+				}
+			},
+			Parse::ValueChainPrevious | Parse::ValueNumber { .. } | Parse::ValueString { .. } => {},
+			Parse::ValueConditional { branches, else_expr, .. } => {
+				for (if_expr, then_expr) in branches {
+					self.visit(if_expr);
+					self.visit(then_expr);
+				}
+				self.visit(else_expr);
+			},
+			Parse::ValueTry { attempts, fallback } => {
+				for parse in attempts {
+					self.visit(parse);
+				}
+				self.visit(fallback);
+			},
+			Parse::ValueLoop { capture, initial_expr, body } => {
+				self.visit(initial_expr);
+				let scope_len = self.scope.len();
+				self.bind_capture(capture);
+				self.visit(body);
+				self.scope.truncate(scope_len);
+			},
+			Parse::ValueBlock { block, .. } => self.visit(block),
+			Parse::ValueFnDef { capture, expr } => {
+				let function = self.new_function();
+				self.visit_fn(function, capture, expr);
+				self.escapes.push((function, None));
+			},
+			Parse::ValueTypeDef { .. } => {},
+			Parse::ValueNew { value, .. } => self.visit(value),
+			Parse::ValueRequest { span, .. } => {
+				let request = Request { span: *span, ty: self.exchanged_type(*span) };
+				if request.provided_by(&self.providers) {
+					return;
+				}
+				match self.function {
+					Some(function) => {
+						self.requests.push(request);
+						self.functions[function].requests.push(self.requests.len() - 1);
+					},
+					None => self.errors.push(ErrorInRequests::Unprovided { request: request.span, ty: request.ty, caller: None })
+				}
+			},
+			Parse::ValueProvide { span, value, block } => {
+				self.visit(value);
+				let provided = self.exchanged_type(*span);
+				self.providers.push(provided);
+				self.visit(block);
+				self.providers.pop();
+			},
+			Parse::ValueTuple { entries } => {
+				for entry in entries {
+					let Parse::ValueTupleEntry { value, .. } = entry else { unreachable!("tuples only contain tuple entries") };
+					self.visit(value);
+				}
+			},
+			Parse::Let { .. } | Parse::ValueTupleEntry { .. } | Parse::CaptureName { .. } | Parse::CaptureTuple { .. } | Parse::CaptureTupleEntry { .. } | Parse::CaptureView { .. } | Parse::TypeName { .. } | Parse::TypeTuple { .. } | Parse::TypeTupleEntry { .. } | Parse::TypeView { .. } => {
+				unreachable!("only visited as part of their parent")
+			}
+		}
+	}
+
+	// Calls to names which aren't known to be functions, like function parameters, are passed over. They can only reach
+	// functions used as values, whose requests are reported where they're used instead.
+	fn call(&mut self, name: &str, span: Span) {
+		let Some(callee) = self.function_named(name) else { return };
+		let call = Call { callee, span, providers: self.providers.clone() };
+		match self.function {
+			Some(function) => self.functions[function].calls.push(call),
+			None => self.outermost_calls.push(call)
+		}
+	}
+
+	fn function_named(&self, name: &str) -> Option<usize> {
+		match self.scope.iter().rev().find(|(bound_name, _)| bound_name == name) {
+			Some((_, function)) => *function,
+			None => None
+		}
+	}
+
+	// Everything the type checker reaches is recorded, but types it can't work out are unknown anyway.
+	fn exchanged_type(&self, span: Span) -> Type {
+		self.exchanged.get(&span.index).cloned().unwrap_or(Type::Unknown)
+	}
+
+	// Captured values aren't known to be functions, even if they are.
+	fn bind_capture(&mut self, capture: &Parse) {
+		match capture {
+			Parse::CaptureName { name, .. } | Parse::CaptureView { name, .. } => self.scope.push((name.clone(), None)),
+			Parse::CaptureTuple { entries, .. } => {
+				for entry in entries {
+					match entry {
+						Parse::CaptureTupleEntry { capture: Some(capture), .. } => self.bind_capture(capture),
+						Parse::CaptureTupleEntry { matcher: Some(Token { ty: TokenType::Name { name }, .. }), capture: None, .. } => {
+							self.scope.push((name.clone(), None));
+						},
+						_ => {}
+					}
+				}
+			},
+			_ => unreachable!("captures are always names, tuples or views")
+		}
+	}
+
+	// Requests passed on by each function are found by following calls until nothing new is passed on. Functions can
+	// call themselves, so this can't be done in one pass over the calls.
+	fn finish(mut self) -> Result<(), Vec<ErrorInRequests>> {
+		let mut passed_on: Vec<Vec<usize>> = self.functions.iter().map(|function| function.requests.clone()).collect();
+		let mut changed = true;
+		while changed {
+			changed = false;
+			for (function, Function { calls, .. }) in self.functions.iter().enumerate() {
+				for call in calls {
+					for request in passed_on[call.callee].clone() {
+						if !call.provides(&self.requests[request]) && !passed_on[function].contains(&request) {
+							passed_on[function].push(request);
+							changed = true;
+						}
+					}
+				}
+			}
+		}
+		let mut unchecked = vec![];
+		for &(function, through) in &self.escapes {
+			for &request in &passed_on[function] {
+				if !unchecked.contains(&request) {
+					unchecked.push(request);
+					let request = &self.requests[request];
+					self.errors.push(ErrorInRequests::Unchecked { request: request.span, ty: request.ty.clone(), through });
+				}
+			}
+		}
+		for call in &self.outermost_calls {
+			for &request in &passed_on[call.callee] {
+				let request = &self.requests[request];
+				if !call.provides(request) {
+					self.errors.push(ErrorInRequests::Unprovided { request: request.span, ty: request.ty.clone(), caller: Some(call.span) });
+				}
+			}
+		}
+		if self.errors.is_empty() {
+			Ok(())
+		} else {
+			self.errors.sort_by_key(|error| error.span().index);
+			Err(self.errors)
+		}
+	}
+}
\ No newline at end of file
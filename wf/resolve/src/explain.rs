@@ -0,0 +1,17 @@
+use crate::ErrorInResolve;
+
+pub fn explain_resolve_error(error: &ErrorInResolve) {
+	match error {
+		ErrorInResolve::UndefinedName { span, name } => {
+			println!("name `{name}` at {}:{} is not declared", span.line, span.line_index);
+		},
+		ErrorInResolve::UsedBeforeDeclared { span, name, declared } => {
+			println!("name `{name}` at {}:{} is used before it's declared at {}:{}", span.line, span.line_index, declared.line, declared.line_index);
+			println!("expected declarations to come before the expressions using them");
+		},
+		ErrorInResolve::DuplicateName { span, name, first } => {
+			println!("name `{name}` at {}:{} is already captured at {}:{}", span.line, span.line_index, first.line, first.line_index);
+			println!("expected each name to be captured once per capture");
+		}
+	}
+}
\ No newline at end of file
@@ -0,0 +1,321 @@
+// Name resolution.
+//
+// Binds every use of a name to the declaration it refers to, following the
+// scopes formed by the file, blocks, functions and loops. Declarations happen
+// top-down, so a name can only be used after the let declaring it.
+
+use wf_parse::Parse;
+use wf_token::{Span, Token, TokenType};
+
+pub mod explain;
+
+#[derive(Debug, Clone)]
+pub enum ErrorInResolve {
+	UndefinedName { span: Span, name: String },
+	UsedBeforeDeclared { span: Span, name: String, declared: Span },
+	// The same name was captured twice by one capture.
+	DuplicateName { span: Span, name: String, first: Span }
+}
+
+#[derive(Debug, Clone)]
+pub struct Declaration {
+	pub name: String,
+	pub span: Span
+}
+
+#[derive(Debug, Default)]
+pub struct Scope {
+	pub parent: Option<usize>,
+	// In order of declaration. Later declarations of a name shadow earlier ones.
+	pub declarations: Vec<Declaration>,
+	// Declared further down the scope than has been resolved so far.
+	upcoming: Vec<Declaration>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Binding {
+	Builtin,
+	Declaration { scope: usize, index: usize }
+}
+
+#[derive(Debug, Default)]
+pub struct Resolution {
+	pub scopes: Vec<Scope>,
+	// Every use of a name, by the span it was used at.
+	pub uses: Vec<(Span, Binding)>
+}
+
+#[derive(Default)]
+struct Resolver {
+	resolution: Resolution,
+	current: Option<usize>,
+	errors: Vec<ErrorInResolve>
+}
+
+pub fn resolve(syntax: &[Parse]) -> Result<Resolution, Vec<ErrorInResolve>> {
+	let mut resolver = Resolver::default();
+	resolver.enter_scope(upcoming_in(syntax));
+	for parse in syntax {
+		resolver.visit_let(parse);
+	}
+	if resolver.errors.is_empty() {
+		Ok(resolver.resolution)
+	} else {
+		Err(resolver.errors)
+	}
+}
+
+impl Resolver {
+	fn enter_scope(&mut self, upcoming: Vec<Declaration>) {
+		self.resolution.scopes.push(Scope { parent: self.current, declarations: vec![], upcoming });
+		self.current = Some(self.resolution.scopes.len() - 1);
+	}
+
+	fn exit_scope(&mut self) {
+		self.current = self.current.and_then(|scope| self.resolution.scopes[scope].parent);
+	}
+
+	fn visit_let(&mut self, parse: &Parse) {
+		let Parse::Let { capture, expr, .. } = parse else { unreachable!("only lets can be declared") };
+		self.visit_types(capture);
+		self.visit_capture_views(capture);
+		// A let can't see what it's declaring, but it's not coming up later either.
+		let mut declaring = vec![];
+		captured_names(capture, &mut declaring);
+		let scope = &mut self.resolution.scopes[self.current.expect("declarations are always made in a scope")];
+		scope.upcoming.retain(|upcoming| declaring.iter().all(|declaration| declaration.span != upcoming.span));
+		match (capture.as_ref(), expr.as_ref()) {
+			// Declared before the body is visited, so that the function can call itself.
+			(Parse::CaptureName { .. }, Parse::ValueFnDef { .. }) => {
+				self.declare(capture);
+				self.visit(expr);
+			},
+			_ => {
+				self.visit(expr);
+				self.declare(capture);
+			}
+		}
+	}
+
+	fn declare(&mut self, capture: &Parse) {
+		let mut declarations = vec![];
+		captured_names(capture, &mut declarations);
+		let scope = &mut self.resolution.scopes[self.current.expect("declarations are always made in a scope")];
+		for (index, declaration) in declarations.iter().enumerate() {
+			if let Some(first) = declarations[..index].iter().find(|first| first.name == declaration.name) {
+				self.errors.push(ErrorInResolve::DuplicateName { span: declaration.span, name: declaration.name.clone(), first: first.span });
+			}
+		}
+		scope.declarations.extend(declarations);
+	}
+
+	// View captures declare the viewed name again where it was viewed from, so they use the view rather than declare it.
+	fn visit_capture_views(&mut self, capture: &Parse) {
+		match capture {
+			Parse::CaptureView { span, name, .. } => self.use_name(name, *span),
+			Parse::CaptureTuple { entries, .. } => {
+				for entry in entries {
+					if let Parse::CaptureTupleEntry { capture: Some(capture), .. } = entry {
+						self.visit_capture_views(capture);
+					}
+				}
+			},
+			_ => {}
+		}
+	}
+
+	// Finds the type names used by a type, or by the annotations of a capture.
+	fn visit_types(&mut self, parse: &Parse) {
+		match parse {
+			Parse::TypeName { span, name } => self.use_type_name(name, *span),
+			Parse::TypeTuple { entries, .. } => {
+				for entry in entries {
+					self.visit_types(entry);
+				}
+			},
+			Parse::CaptureTuple { entries, ty } => {
+				for entry in entries {
+					self.visit_types(entry);
+				}
+				if let Some(ty) = ty {
+					self.visit_types(ty);
+				}
+			},
+			Parse::TypeTupleEntry { ty, .. } | Parse::TypeView { ty, .. } => self.visit_types(ty),
+			Parse::CaptureTupleEntry { capture, ty, .. } => {
+				if let Some(capture) = capture {
+					self.visit_types(capture);
+				}
+				if let Some(ty) = ty {
+					self.visit_types(ty);
+				}
+			},
+			Parse::CaptureName { ty, .. } | Parse::CaptureView { ty, .. } => if let Some(ty) = ty {
+				self.visit_types(ty);
+			},
+			_ => unreachable!("only types and captures hold types")
+		}
+	}
+
+	fn visit(&mut self, parse: &Parse) {
+		match parse {
+			Parse::Block { lets, expr } => {
+				self.enter_scope(upcoming_in(lets));
+				for parse in lets {
+					self.visit_let(parse);
+				}
+				self.visit(expr);
+				self.exit_scope();
+			},
+			Parse::ExprThrow { expr } => self.visit(expr),
+			Parse::ExprChain { first, rest } => {
+				self.visit(first);
+				for parse in rest {
+					self.visit(parse);
+				}
+			},
+			Parse::ExprAutoChainFirstFnEval { name, span, datum } => {
+				self.use_name(name, *span);
+				if let Some(datum) = datum {
+					self.visit(datum);
+				}
+			},
+			Parse::ExprInfix { first, rest } => {
+				self.visit(first);
+				for (_, _, parse) in rest {
+					self.visit(parse);
+				}
+			},
+			Parse::ExprRange { first, rest, .. } => {
+				self.visit(first);
+				for (_, _, parse) in rest {
+					self.visit(parse);
+				}
+			},
+			Parse::ExprPrefix { term, .. } | Parse::ExprAccess { term, .. } => self.visit(term),
+			Parse::ValueFnEval { name, span, datum } => {
+				self.use_name(name, *span);
+				self.visit(datum);
+			},
+			Parse::ValueName { span, name } | Parse::ValueView { span, name } => self.use_name(name, *span),
+			Parse::ValueChainPrevious | Parse::ValueNumber { .. } | Parse::ValueString { .. } => {},
+			Parse::ValueConditional { branches, else_expr, .. } => {
+				for (if_expr, then_expr) in branches {
+					self.visit(if_expr);
+					self.visit(then_expr);
+				}
+				self.visit(else_expr);
+			},
+			Parse::ValueTry { attempts, fallback } => {
+				for parse in attempts {
+					self.visit(parse);
+				}
+				self.visit(fallback);
+			},
+			Parse::ValueLoop { capture, initial_expr, body } => {
+				self.visit(initial_expr);
+				self.enter_scope(vec![]);
+				self.visit_types(capture);
+				self.visit_capture_views(capture);
+				self.declare(capture);
+				self.visit(body);
+				self.exit_scope();
+			},
+			Parse::ValueBlock { block, .. } => self.visit(block),
+			Parse::ValueFnDef { capture, expr } => {
+				self.enter_scope(vec![]);
+				self.visit_types(capture);
+				self.visit_capture_views(capture);
+				self.declare(capture);
+				self.visit(expr);
+				self.exit_scope();
+			},
+			Parse::ValueTypeDef { ty } | Parse::ValueRequest { ty, .. } => self.visit_types(ty),
+			Parse::ValueNew { ty, value } => {
+				self.visit_types(ty);
+				self.visit(value);
+			},
+			Parse::ValueProvide { value, block, .. } => {
+				self.visit(value);
+				self.visit(block);
+			},
+			Parse::ValueTuple { entries } => {
+				for entry in entries {
+					let Parse::ValueTupleEntry { value, .. } = entry else { unreachable!("tuples only contain tuple entries") };
+					self.visit(value);
+				}
+			},
+			Parse::Let { .. } | Parse::ValueTupleEntry { .. } | Parse::CaptureName { .. } | Parse::CaptureTuple { .. } | Parse::CaptureTupleEntry { .. } | Parse::CaptureView { .. } | Parse::TypeName { .. } | Parse::TypeTuple { .. } | Parse::TypeTupleEntry { .. } | Parse::TypeView { .. } => {
+				unreachable!("only visited as part of their parent")
+			}
+		}
+	}
+
+	// Looks through the scopes in the same order as evaluation - `true` and `false` can't be shadowed, but builtin types can.
+	fn use_name(&mut self, name: &str, span: Span) {
+		if name == "true" || name == "false" {
+			self.resolution.uses.push((span, Binding::Builtin));
+		} else {
+			self.bind(name, span, &["int", "num", "bool", "str"]);
+		}
+	}
+
+	// Type names are declared by lets like any other name, so they're found the same way. Only types can use `ty`.
+	fn use_type_name(&mut self, name: &str, span: Span) {
+		self.bind(name, span, &["int", "num", "bool", "str", "ty"]);
+	}
+
+	fn bind(&mut self, name: &str, span: Span, builtins: &[&str]) {
+		let binding = self.find(|scope| scope.declarations.iter().rposition(|declaration| declaration.name == name))
+			.map(|(scope, index)| Binding::Declaration { scope, index })
+			.or_else(|| builtins.contains(&name).then_some(Binding::Builtin));
+		if let Some(binding) = binding {
+			self.resolution.uses.push((span, binding));
+		} else if let Some((scope, index)) = self.find(|scope| scope.upcoming.iter().position(|upcoming| upcoming.name == name)) {
+			let declared = self.resolution.scopes[scope].upcoming[index].span;
+			self.errors.push(ErrorInResolve::UsedBeforeDeclared { span, name: name.to_string(), declared });
+		} else {
+			self.errors.push(ErrorInResolve::UndefinedName { span, name: name.to_string() });
+		}
+	}
+
+	// Searches from the innermost scope outwards.
+	fn find(&self, search: impl Fn(&Scope) -> Option<usize>) -> Option<(usize, usize)> {
+		let mut scope = self.current;
+		while let Some(id) = scope {
+			if let Some(index) = search(&self.resolution.scopes[id]) {
+				return Some((id, index));
+			}
+			scope = self.resolution.scopes[id].parent;
+		}
+		None
+	}
+}
+
+fn upcoming_in(lets: &[Parse]) -> Vec<Declaration> {
+	let mut upcoming = vec![];
+	for parse in lets {
+		if let Parse::Let { capture, .. } = parse {
+			captured_names(capture, &mut upcoming);
+		}
+	}
+	upcoming
+}
+
+fn captured_names(capture: &Parse, declarations: &mut Vec<Declaration>) {
+	match capture {
+		Parse::CaptureName { span, name, .. } => declarations.push(Declaration { name: name.clone(), span: *span }),
+		Parse::CaptureTuple { entries, .. } => {
+			for entry in entries {
+				match entry {
+					Parse::CaptureTupleEntry { capture: Some(capture), .. } => captured_names(capture, declarations),
+					Parse::CaptureTupleEntry { matcher: Some(Token { ty: TokenType::Name { name }, span }), capture: None, .. } => {
+						declarations.push(Declaration { name: name.clone(), span: *span });
+					},
+					_ => {}
+				}
+			}
+		},
+		_ => {}
+	}
+}
\ No newline at end of file
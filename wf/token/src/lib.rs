@@ -4,6 +4,8 @@
 // specifically part of any syntax, but could still be included in spans like
 // names, comments or strings.
 
+use std::iter;
+
 use serde::Serialize;
 use wf_lookahead::Lookahead;
 
@@ -22,7 +24,93 @@ pub struct Token {
 	pub span: Span
 }
 
-#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Base { Binary, Decimal, Hexadecimal }
+
+impl Base {
+	pub fn radix(&self) -> u32 {
+		match self {
+			Base::Binary => 2,
+			Base::Decimal => 10,
+			Base::Hexadecimal => 16
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum NumberLiteral {
+	Int { value: i64, base: Base },
+	Num { value: f64, base: Base }
+}
+
+impl NumberLiteral {
+	// Names reserved for useful special numbers.
+	pub fn special(name: &str) -> Option<Self> {
+		let value = match name {
+			"nan" => f64::NAN,
+			"inf" => f64::INFINITY,
+			"eul" => std::f64::consts::E,
+			"tau" => std::f64::consts::TAU,
+			"phi" => 1.618_033_988_749_895,
+			_ => return None
+		};
+		Some(NumberLiteral::Num { value, base: Base::Decimal })
+	}
+
+	// Expects the digits after any change of base prefix. Underscores are ignored.
+	pub fn parse(digits: &str, base: Base) -> Result<Self, &'static str> {
+		let digits = digits.replace('_', "");
+		let radix = base.radix();
+		let (whole, fraction) = match digits.split_once('.') {
+			Some((whole, fraction)) => (whole, Some(fraction)),
+			None => (digits.as_str(), None)
+		};
+		if whole.is_empty() {
+			return Err("no digits were written");
+		} else if fraction.is_some_and(|fraction| fraction.contains('.')) {
+			return Err("only one dot can be written");
+		} else if !whole.chars().chain(fraction.unwrap_or_default().chars()).all(|char| char.is_digit(radix)) {
+			return Err("digits must be valid for the base");
+		}
+		match fraction {
+			None => {
+				let value = i64::from_str_radix(whole, radix).map_err(|_| "integer is too large")?;
+				Ok(NumberLiteral::Int { value, base })
+			},
+			// Rust already finds the floating point value that minimally diverges from decimal digits.
+			Some(_) if base == Base::Decimal => {
+				let value = digits.parse().map_err(|_| "digits must be valid for the base")?;
+				Ok(NumberLiteral::Num { value, base })
+			},
+			Some(fraction) => {
+				let digit = |char: char| char.to_digit(radix).expect("digits were checked earlier") as f64;
+				let mut value = whole.chars().fold(0.0, |value, char| value * radix as f64 + digit(char));
+				let mut scale = 1.0;
+				for char in fraction.chars() {
+					scale /= radix as f64;
+					value += digit(char) * scale;
+				}
+				Ok(NumberLiteral::Num { value, base })
+			}
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Delimited { String, RawString, LongComment, BacktickedName }
+
+impl Delimited {
+	pub fn external_name(&self) -> &'static str {
+		match self {
+			Delimited::String => "string",
+			Delimited::RawString => "raw string",
+			Delimited::LongComment => "long comment",
+			Delimited::BacktickedName => "backticked name"
+		}
+	}
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
 pub enum TokenType {
 	#[default]
 	Unexpected,
@@ -30,7 +118,13 @@ pub enum TokenType {
 	Whitespace,
 	Comment,
 	Name { name: String },
-	String { string: String },
+	Number { value: NumberLiteral },
+	MalformedNumber { problem: &'static str },
+	// `raw` is the source text between the quotes, before any insert sequences are processed.
+	String { string: String, raw: String },
+	MalformedString { problem: &'static str },
+	// The end of the file was reached before the closing delimiter.
+	Unterminated { kind: Delimited },
 
 	Throw,
 	Catch,
@@ -42,6 +136,11 @@ pub enum TokenType {
 	Or,
 	Fn,
 	If,
+	Req,
+	Prov,
+	Try,
+	Ty,
+	New,
 
 	Ellipsis,
 	DoubleSlash,
@@ -55,6 +154,8 @@ pub enum TokenType {
 	CloseBracket,
 	OpenParen,
 	CloseParen,
+	OpenBrace,
+	CloseBrace,
 	Comma,
 	Dot,
 	Colon,
@@ -65,6 +166,7 @@ pub enum TokenType {
 	Percent,
 	Caret,
 	Hash,
+	At,
 	Equal,
 	Bang,
 	Less,
@@ -89,16 +191,21 @@ impl TokenType {
 		exact!("catch", Catch),
 
 		exact!("loop", Loop),
+		exact!("prov", Prov),
 		exact!("then", Then),
 		exact!("else", Else),
 
 		exact!("and", And),
 		exact!("let", Let),
+		exact!("req", Req),
+		exact!("try", Try),
+		exact!("new", New),
 		exact!("...", Ellipsis),
 
 		exact!("or", Or),
 		exact!("fn", Fn),
 		exact!("if", If),
+		exact!("ty", Ty),
 		exact!("//", DoubleSlash),
 		exact!("/^", SlashCaret),
 		exact!("!=", BangEqual),
@@ -112,6 +219,8 @@ impl TokenType {
 		exact!("]", CloseBracket),
 		exact!("(", OpenParen),
 		exact!(")", CloseParen),
+		exact!("{", OpenBrace),
+		exact!("}", CloseBrace),
 		exact!(",", Comma),
 		exact!(".", Dot),
 		exact!(":", Colon),
@@ -122,6 +231,7 @@ impl TokenType {
 		exact!("%", Percent),
 		exact!("^", Caret),
 		exact!("#", Hash),
+		exact!("@", At),
 		exact!("=", Equal),
 		exact!("!", Bang),
 		exact!("<", Less),
@@ -138,7 +248,11 @@ impl TokenType {
 			Whitespace => "whitespace",
 			Comment => "comment",
 			Name { .. } => "name",
+			Number { .. } => "number",
+			MalformedNumber { .. } => "malformed_number",
 			String { .. } => "string",
+			MalformedString { .. } => "malformed_string",
+			Unterminated { .. } => "unterminated",
 
 			Throw => "throw",
 			Catch => "catch",
@@ -150,6 +264,11 @@ impl TokenType {
 			Or => "or",
 			Fn => "fn",
 			If => "if",
+			Req => "req",
+			Prov => "prov",
+			Try => "try",
+			Ty => "ty",
+			New => "new",
 
 			Ellipsis => "ellipsis",
 			DoubleSlash => "double_slash",
@@ -163,6 +282,8 @@ impl TokenType {
 			CloseBracket => "close_bracket",
 			OpenParen => "open_paren",
 			CloseParen => "close_paren",
+			OpenBrace => "open_brace",
+			CloseBrace => "close_brace",
 			Comma => "comma",
 			Dot => "dot",
 			Colon => "colon",
@@ -173,6 +294,7 @@ impl TokenType {
 			Percent => "percent",
 			Caret => "caret",
 			Hash => "hash",
+			At => "at",
 			Equal => "equal",
 			Bang => "bang",
 			Less => "less",
@@ -183,14 +305,17 @@ impl TokenType {
 }
 
 pub struct Tokeniser<Input: Iterator<Item = u8>> {
-    bytes: Lookahead<4, u8, Input>,
+	// Must be able to see the whole of the longest exact token at once, plus the byte after it.
+    bytes: Lookahead<6, u8, Input>,
 	line: usize,
-	line_index: usize
+	line_index: usize,
+	// The whitespace at the start of the current line.
+	line_indentation: Vec<u8>
 }
 
 impl<Input: Iterator<Item = u8>> Tokeniser<Input> {
 	pub fn new(input: Input) -> Self {
-		Self { bytes: Lookahead::new(input), line: 1, line_index: 1 }
+		Self { bytes: Lookahead::new(input), line: 1, line_index: 1, line_indentation: vec![] }
 	}
 }
 
@@ -215,16 +340,21 @@ impl<Input: Iterator<Item = u8>> Iterator for Tokeniser<Input> {
 					if char == b'\r' {
 						self.line += 1;
 						self.line_index = 1;
+						self.line_indentation.clear();
 						line_feed_should_increment = false;
 						continue;
 					} else if char == b'\n' {
 						if line_feed_should_increment {
 							self.line += 1;
 							self.line_index = 1;
+							self.line_indentation.clear();
 						}
 						line_feed_should_increment = true;
 						continue;
 					} else {
+						if matches!(char, b' ' | b'\t') && self.line_index == self.line_indentation.len() + 1 {
+							self.line_indentation.push(char);
+						}
 						self.line_index += 1;
 						line_feed_should_increment = true;
 					}
@@ -248,13 +378,15 @@ impl<Input: Iterator<Item = u8>> Iterator for Tokeniser<Input> {
 			if num_hyphens > 2 {
 				consume!(2); // Consume the two hyphens we didn't initially consume.
 				let mut end_hyphens = 0;
+				let mut terminated = false;
 				while let Some(&char) = bytes.peek(0) {
 					consume!(1);
 					if char == b'-' {
 						end_hyphens += 1;
-						if end_hyphens == num_hyphens { break; }
+						if end_hyphens == num_hyphens { terminated = true; break; }
 					} else { end_hyphens = 0; }
 				}
+				if !terminated { ret!(TokenType::Unterminated { kind: Delimited::LongComment }); }
 				ret!(TokenType::Comment);
 			}
 
@@ -271,7 +403,10 @@ impl<Input: Iterator<Item = u8>> Iterator for Tokeniser<Input> {
 		// Exact tokens
 		for (expect, token) in TokenType::SORTED_EXACT_TOKENS {
 			let found_match = expect.iter().enumerate().all(|(offset, &expected)| bytes.peek(offset) == Some(&expected));
-			if found_match {
+			// Keywords only match whole words, so that names like `android` aren't split into `and` + `roid`.
+			let inside_name = expect[0].is_ascii_alphabetic()
+				&& bytes.peek(expect.len()).is_some_and(|&char| char.is_ascii_alphanumeric() || char == b'_');
+			if found_match && !inside_name {
 				consume!(expect.len());
 				ret!(token.clone());
 			}
@@ -283,6 +418,34 @@ impl<Input: Iterator<Item = u8>> Iterator for Tokeniser<Input> {
 
 		let mut content_u8 = vec![];
 
+		// Number
+		if start_char.is_ascii_digit() {
+			let base = match bytes.peek(1) {
+				Some(b'x') if start_char == b'0' => Base::Hexadecimal,
+				Some(b'b') if start_char == b'0' => Base::Binary,
+				_ => Base::Decimal
+			};
+			if base != Base::Decimal { consume!(2); }
+			while let Some(&char) = bytes.peek(0) {
+				let digit_follows = bytes.peek(1).is_some_and(|&next| next.is_ascii_digit() || (base == Base::Hexadecimal && next.is_ascii_hexdigit()));
+				if char.is_ascii_alphanumeric() || char == b'_' || (char == b'.' && digit_follows) {
+					consume!(1);
+					content_u8.push(char);
+				} else {
+					break;
+				}
+			}
+			let digits = bytes_to_utf8_lossy!(content_u8);
+			// Names like `360_degrees` only start with digits, and aren't reserved as numbers.
+			if base == Base::Decimal && !digits.contains('.') && digits.bytes().any(|char| char.is_ascii_alphabetic()) {
+				ret!(TokenType::Name { name: digits });
+			}
+			match NumberLiteral::parse(&digits, base) {
+				Ok(value) => { ret!(TokenType::Number { value }); },
+				Err(problem) => { ret!(TokenType::MalformedNumber { problem }); }
+			}
+		}
+
 		// Name
 		{
 			// Unbackticked name
@@ -290,6 +453,7 @@ impl<Input: Iterator<Item = u8>> Iterator for Tokeniser<Input> {
 			let mut can_add_dot = true;
 
 			while let Some(&char) = bytes.peek(0) {
+				#[allow(clippy::collapsible_if)]
 				if char.is_ascii_alphanumeric() || char == b'_' {
 					digit_preceding = char.is_ascii_digit();
 					consume!(1);
@@ -306,22 +470,31 @@ impl<Input: Iterator<Item = u8>> Iterator for Tokeniser<Input> {
 				}
 				break;
 			}
-			if bytes.position() > start_position { ret!(TokenType::Name { name: bytes_to_utf8_lossy!(content_u8) }); }
+			if bytes.position() > start_position {
+				let name = bytes_to_utf8_lossy!(content_u8);
+				match NumberLiteral::special(&name) {
+					Some(value) => { ret!(TokenType::Number { value }); },
+					None => { ret!(TokenType::Name { name }); }
+				}
+			}
 
 			// Backticked name
 			if start_char == b'`' {
 				consume!(1);
+				let mut terminated = false;
 				while let Some(&char) = bytes.peek(0) {
 					consume!(1);
-					if char == b'`' { break; }
+					if char == b'`' { terminated = true; break; }
 					content_u8.push(char);
 				}
+				if !terminated { ret!(TokenType::Unterminated { kind: Delimited::BacktickedName }); }
 				ret!(TokenType::Name { name: bytes_to_utf8_lossy!(content_u8) });
 			}
 		}
 
 		// String
 		{
+			let mut dedent = Dedent::new(self.line_indentation.clone());
 			let mut num_quotes = 0;
 			while let Some(&char) = bytes.peek(num_quotes.min(2)) && matches!(char, b'"') {
 				num_quotes += 1;
@@ -332,40 +505,116 @@ impl<Input: Iterator<Item = u8>> Iterator for Tokeniser<Input> {
 			// Empty short string
 			if num_quotes == 2 {
 				consume!(2);
-				ret!(TokenType::String { string: bytes_to_utf8_lossy!(content_u8) });
+				ret!(TokenType::String { string: String::new(), raw: String::new() });
 			}
 			
 			// Short string
 			if num_quotes == 1 {
 				consume!(1);
-				let mut escaped = false;
+				let mut raw_u8 = vec![];
+				let mut problem = None;
+				let mut terminated = false;
 				while let Some(&char) = bytes.peek(0) {
 					consume!(1);
-					if escaped { escaped = false; } 
-					else if char == b'"' { break; }
-					content_u8.push(char);
+					if char == b'"' { terminated = true; break; }
+					raw_u8.push(char);
+					// Line feeds aren't included in short strings.
+					if matches!(char, b'\n' | b'\r') {
+						dedent.line_break();
+						continue;
+					}
+					if dedent.skip(char) { continue; }
+					if char != b'\\' {
+						content_u8.push(char);
+						continue;
+					}
+					// Insert sequence
+					let Some(&insert) = bytes.peek(0) else { break };
+					consume!(1);
+					raw_u8.push(insert);
+					content_u8.push(match insert {
+						b'n' => b'\n',
+						b't' => b'\t',
+						b'\\' => b'\\',
+						b'"' => b'"',
+						_ => {
+							problem = Some("unknown insert sequence - expected \\n, \\t, \\\\ or \\\"");
+							insert
+						}
+					});
 				}
-				ret!(TokenType::String { string: bytes_to_utf8_lossy!(content_u8) });
+				if !terminated { ret!(TokenType::Unterminated { kind: Delimited::String }); }
+				if let Some(problem) = problem { ret!(TokenType::MalformedString { problem }); }
+				ret!(TokenType::String { string: bytes_to_utf8_lossy!(content_u8), raw: bytes_to_utf8_lossy!(raw_u8) });
 			}
 			
 			// Raw string
 			if num_quotes != 0 {
-				consume!(2); // Consume the two hyphens we didn't initially consume.
+				consume!(2); // Consume the two quotes we didn't initially consume.
+				let mut raw_u8 = vec![];
 				let mut end_quotes = 0;
+				let mut terminated = false;
 				while let Some(&char) = bytes.peek(0) {
 					consume!(1);
+					let after_carriage_return = raw_u8.last() == Some(&b'\r');
+					raw_u8.push(char);
+					// Quotes might be the end of the string, so they're held back until that's known.
 					if char == b'"' {
 						end_quotes += 1;
-						if end_quotes == num_quotes { break; }
-					} else { end_quotes = 0; }
+						if end_quotes == num_quotes { terminated = true; break; }
+						continue;
+					}
+					if end_quotes > 0 {
+						content_u8.extend(iter::repeat_n(b'"', end_quotes));
+						end_quotes = 0;
+						dedent.skip(b'"'); // Quotes aren't indentation, so this stops any further skipping on this line.
+					}
+					// Line feeds are kept in raw strings, but always as LF.
+					if char == b'\r' || (char == b'\n' && !after_carriage_return) {
+						dedent.line_break();
+						content_u8.push(b'\n');
+						continue;
+					} else if char == b'\n' {
+						continue;
+					}
+					if dedent.skip(char) { continue; }
 					content_u8.push(char);
 				}
-				content_u8.truncate(content_u8.len() - end_quotes + 1);
-				ret!(TokenType::String { string: bytes_to_utf8_lossy!(content_u8) });
+				if !terminated { ret!(TokenType::Unterminated { kind: Delimited::RawString }); }
+				raw_u8.truncate(raw_u8.len() - num_quotes);
+				ret!(TokenType::String { string: bytes_to_utf8_lossy!(content_u8), raw: bytes_to_utf8_lossy!(raw_u8) });
 			}
 		}
 
 		consume!(1); // Without this, the tokeniser doesn't move forward.
 		ret!(TokenType::Unexpected);
 	}
+}
+
+// Skips indentation at the start of each line of a multi-line string, for as long as it matches the indentation of the
+// line that the string started on.
+struct Dedent {
+	indentation: Vec<u8>,
+	matched: Option<usize>
+}
+
+impl Dedent {
+	fn new(indentation: Vec<u8>) -> Self {
+		Self { indentation, matched: None }
+	}
+
+	fn line_break(&mut self) {
+		self.matched = Some(0);
+	}
+
+	fn skip(&mut self, char: u8) -> bool {
+		let Some(matched) = self.matched else { return false };
+		if self.indentation.get(matched) == Some(&char) {
+			self.matched = Some(matched + 1);
+			true
+		} else {
+			self.matched = None;
+			false
+		}
+	}
 }
\ No newline at end of file
@@ -0,0 +1,62 @@
+// Round-trips randomly generated raw strings through the tokeniser, checking that the content comes back out exactly as
+// it went in, however many quotes it contains.
+
+use wf_token::{Token, TokenType, Tokeniser};
+
+const CASES: usize = 2000;
+const MAX_LENGTH: usize = 24;
+// Quotes are weighted heavily, so that runs of them turn up often.
+const ALPHABET: &[u8] = b"\"\"\"\"ab -\\\n\t";
+
+// Xorshift, so that failures are reproducible without pulling in a dependency.
+struct Random(u64);
+
+impl Random {
+	fn next(&mut self) -> u64 {
+		self.0 ^= self.0 << 13;
+		self.0 ^= self.0 >> 7;
+		self.0 ^= self.0 << 17;
+		self.0
+	}
+
+	fn below(&mut self, max: usize) -> usize {
+		(self.next() % max as u64) as usize
+	}
+}
+
+// Raw strings can't start or end with a quote, as it would be read as part of the delimiter.
+fn random_content(random: &mut Random) -> Vec<u8> {
+	let length = 1 + random.below(MAX_LENGTH);
+	let mut content: Vec<u8> = (0..length).map(|_| ALPHABET[random.below(ALPHABET.len())]).collect();
+	for index in [0, length - 1] {
+		if content[index] == b'"' { content[index] = b'a'; }
+	}
+	content
+}
+
+fn longest_quote_run(content: &[u8]) -> usize {
+	content.split(|&char| char != b'"').map(|run| run.len()).max().unwrap_or(0)
+}
+
+#[test]
+fn raw_strings_round_trip() {
+	let mut random = Random(0x5eed_1234_abcd_0001);
+	for _ in 0..CASES {
+		let content = random_content(&mut random);
+		// The delimiter has to be longer than any run of quotes inside the string.
+		let num_quotes = 3.max(longest_quote_run(&content) + 1) + random.below(3);
+		let delimiter = vec![b'"'; num_quotes];
+		let source = [delimiter.as_slice(), &content, &delimiter].concat();
+
+		let tokens: Vec<Token> = Tokeniser::new(source.iter().copied()).collect();
+		let content = String::from_utf8(content).unwrap();
+		let source = String::from_utf8(source).unwrap();
+		assert_eq!(tokens.len(), 1, "{source:?} should be one token, but was {tokens:?}");
+		let Token { ty: TokenType::String { string, raw }, span } = &tokens[0] else {
+			panic!("{source:?} should be a string, but was {:?}", tokens[0].ty);
+		};
+		assert_eq!(string, &content, "content of {source:?}");
+		assert_eq!(raw, &content, "raw content of {source:?}");
+		assert_eq!(span.length, source.len(), "span of {source:?}");
+	}
+}
\ No newline at end of file
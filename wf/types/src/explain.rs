@@ -0,0 +1,72 @@
+use crate::{lock::ErrorInLock, ErrorInTypes};
+
+pub fn explain_type_error(error: &ErrorInTypes) {
+	match error {
+		ErrorInTypes::UntypedCapture { span, name } => {
+			println!("untyped capture `{name}` at {}:{} in let-bound fn", span.line, span.line_index);
+			println!("expected a type for every capture of a function declared by a let, as nothing else says how it will be used");
+		},
+		ErrorInTypes::IncompatibleBranches { span, first, second } => {
+			println!("branches of conditional at {}:{} have incompatible types {first} and {second}", span.line, span.line_index);
+		},
+		ErrorInTypes::ConditionNotBool { span, found } => {
+			println!("condition of conditional at {}:{} is {found}", span.line, span.line_index);
+			println!("expected bool - other values are not converted to bools");
+		},
+		ErrorInTypes::NotAFunction { span, name, found } => {
+			println!("`{name}` at {}:{} is {found}, which can't be evaluated as a function", span.line, span.line_index);
+		},
+		ErrorInTypes::NotAType { span, name } => {
+			println!("`{name}` at {}:{} is not a type", span.line, span.line_index);
+			println!("expected a builtin type, or a name declared by a let as a `ty` definition");
+		},
+		ErrorInTypes::DatumMismatch { span, name, expected, found } => {
+			println!("function `{name}` at {}:{} is given {found}", span.line, span.line_index);
+			println!("expected {expected}");
+		},
+		ErrorInTypes::Mismatch { span, expected, found } => {
+			println!("value of type {found} doesn't match the type at {}:{}", span.line, span.line_index);
+			println!("expected {expected}");
+		},
+		ErrorInTypes::MissingEntry { span, name, found } => {
+			println!("can't access entry `{name}` at {}:{} of {found}", span.line, span.line_index);
+			println!("expected the tuple to have an entry named `{name}` - unnamed entries are named by their position");
+		},
+		ErrorInTypes::MissingCaptureEntry { span, name, found } => {
+			println!("can't capture entry `{name}` at {}:{} from {found}", span.line, span.line_index);
+			println!("expected the tuple to have an entry named `{name}` - unnamed entries are named by their position");
+		},
+		ErrorInTypes::DuplicateEntry { span, name } => {
+			println!("entry named `{name}` at {}:{} is already in the tuple", span.line, span.line_index);
+			println!("expected entries spread into a tuple to have different names and positions to its other entries");
+		},
+		ErrorInTypes::BiOpOperands { span, bi_op, left, right } => {
+			println!("can't apply operator {bi_op:?} at {}:{} to {left} and {right}", span.line, span.line_index);
+		},
+		ErrorInTypes::UnOpOperand { span, un_op, found } => {
+			println!("can't apply operator {un_op:?} at {}:{} to {found}", span.line, span.line_index);
+		}
+	}
+}
+
+pub fn explain_lock_error(error: &ErrorInLock) {
+	match error {
+		ErrorInLock::Drifted { span, name, locked, inferred } => {
+			println!("type of `{name}` at {}:{} has drifted from the lockfile", span.line, span.line_index);
+			println!("locked:   {locked}");
+			println!("inferred: {inferred}");
+		},
+		ErrorInLock::Unlocked { span, name, inferred } => {
+			println!("`{name}` at {}:{} is not in the lockfile", span.line, span.line_index);
+			println!("inferred: {inferred}");
+		},
+		ErrorInLock::NoLongerDeclared { name, locked } => {
+			println!("`{name}` is in the lockfile, but no top-level let declares it any more");
+			println!("locked:   {locked}");
+		},
+		ErrorInLock::MalformedLine { line } => {
+			println!("line {line} of the lockfile is not a signature");
+			println!("expected `let name : type` or `let name = ty definition`");
+		}
+	}
+}
\ No newline at end of file
@@ -0,0 +1,640 @@
+// Static type checker.
+//
+// Types are inferred from locally visible information only - the types of
+// values are worked out from their expressions, and captures are typed by
+// annotations or by the values they capture. Anything that can't be decided
+// locally is left unknown and isn't checked.
+
+use std::{collections::HashMap, mem};
+
+use wf_parse::{positional_index, BiOp, Bound, Parse, UnOp};
+use wf_token::{NumberLiteral, Span, Token, TokenType};
+
+pub mod explain;
+pub mod lock;
+mod ty;
+
+pub use ty::Type;
+use ty::own_entry;
+
+#[derive(Debug, Clone)]
+pub enum ErrorInTypes {
+	UntypedCapture { span: Span, name: String },
+	IncompatibleBranches { span: Span, first: Type, second: Type },
+	ConditionNotBool { span: Span, found: Type },
+	NotAFunction { span: Span, name: String, found: Type },
+	NotAType { span: Span, name: String },
+	DatumMismatch { span: Span, name: String, expected: Type, found: Type },
+	// Points at the type that the value doesn't match.
+	Mismatch { span: Span, expected: Type, found: Type },
+	MissingEntry { span: Span, name: String, found: Type },
+	MissingCaptureEntry { span: Span, name: String, found: Type },
+	DuplicateEntry { span: Span, name: String },
+	BiOpOperands { span: Span, bi_op: BiOp, left: Type, right: Type },
+	UnOpOperand { span: Span, un_op: UnOp, found: Type }
+}
+
+struct Binding {
+	name: String,
+	span: Span,
+	ty: Type,
+	// The type that the name refers to when it's used as a type, such as after `: `.
+	denotes: Option<Type>
+}
+
+#[derive(Default)]
+struct Checker {
+	scope: Vec<Binding>,
+	errors: Vec<ErrorInTypes>,
+	// The innermost let being checked, for problems in parts of a capture without a span of their own.
+	within: Span,
+	chain_previous: Option<Type>,
+	next_definition: usize,
+	// The types of requests and provided values, by the index of their `req` or `prov` keyword.
+	exchanged: HashMap<usize, Type>
+}
+
+// The inferred type of a name declared by a top-level let.
+#[derive(Debug, Clone)]
+pub struct Signature {
+	pub name: String,
+	pub span: Span,
+	pub ty: Type,
+	// Set when the name declares a type.
+	pub denotes: Option<Type>
+}
+
+pub fn check_types(syntax: &[Parse]) -> Result<(), Vec<ErrorInTypes>> {
+	let checker = Checker::check(syntax);
+	if checker.errors.is_empty() {
+		Ok(())
+	} else {
+		Err(checker.errors)
+	}
+}
+
+// In order of declaration, leaving out names shadowed by later top-level lets.
+pub fn infer_signatures(syntax: &[Parse]) -> Vec<Signature> {
+	let checker = Checker::check(syntax);
+	let mut signatures = vec![];
+	for (index, binding) in checker.scope.iter().enumerate() {
+		if checker.scope[index + 1..].iter().any(|later| later.name == binding.name) {
+			continue;
+		}
+		signatures.push(Signature { name: binding.name.clone(), span: binding.span, ty: binding.ty.clone(), denotes: binding.denotes.clone() });
+	}
+	signatures
+}
+
+// Used to match requests to providers, which happens separately from checking types.
+pub fn infer_exchanged_types(syntax: &[Parse]) -> HashMap<usize, Type> {
+	Checker::check(syntax).exchanged
+}
+
+impl Checker {
+	fn check(syntax: &[Parse]) -> Self {
+		let mut checker = Checker::default();
+		for parse in syntax {
+			checker.visit_let(parse);
+		}
+		checker
+	}
+
+	fn bind(&mut self, name: &str, span: Span, ty: Type, denotes: Option<Type>) {
+		self.scope.push(Binding { name: name.to_string(), span, ty, denotes });
+	}
+
+	fn lookup(&self, name: &str) -> Option<&Binding> {
+		self.scope.iter().rev().find(|binding| binding.name == name)
+	}
+
+	fn visit_let(&mut self, parse: &Parse) {
+		let Parse::Let { span, capture, expr } = parse else { unreachable!("only lets can be checked") };
+		let within = mem::replace(&mut self.within, *span);
+		match (capture.as_ref(), expr.as_ref()) {
+			// Nothing else says how a function declared by a let will be used, so all of its captures need types.
+			(Parse::CaptureName { span, name, .. }, Parse::ValueFnDef { capture: fn_capture, expr: fn_expr }) => {
+				let mut untyped = vec![];
+				untyped_names(fn_capture, &mut untyped);
+				for (span, name) in untyped {
+					self.errors.push(ErrorInTypes::UntypedCapture { span, name });
+				}
+				// Declared before the body is checked, so that the function can call itself.
+				let datum = self.capture_type(fn_capture);
+				self.bind(name, *span, Type::Fn { datum: Box::new(datum.clone()), result: Box::new(Type::Unknown) }, None);
+				let result = self.infer_fn(fn_capture, datum.clone(), fn_expr);
+				self.bind(name, *span, Type::Fn { datum: Box::new(datum), result: Box::new(result) }, None);
+			},
+			(Parse::CaptureName { span, name, .. }, Parse::ValueTypeDef { ty }) => {
+				let id = self.new_definition();
+				let definition = Box::new(self.annotation(ty));
+				self.bind(name, *span, Type::Ty, Some(Type::Defined { id, name: name.clone(), definition }));
+			},
+			(capture, expr) => {
+				let ty = self.infer(expr);
+				self.bind_capture(capture, ty);
+			}
+		}
+		self.within = within;
+	}
+
+	fn new_definition(&mut self) -> usize {
+		self.next_definition += 1;
+		self.next_definition
+	}
+
+	fn infer_fn(&mut self, capture: &Parse, datum: Type, expr: &Parse) -> Type {
+		let scope_len = self.scope.len();
+		self.bind_capture(capture, datum);
+		let result = self.infer(expr);
+		self.scope.truncate(scope_len);
+		result
+	}
+
+	fn bind_capture(&mut self, capture: &Parse, found: Type) {
+		match capture {
+			Parse::CaptureName { span, name, ty } => {
+				let ty = self.check_annotation(ty.as_deref(), found);
+				self.bind(name, *span, ty, None);
+			},
+			Parse::CaptureTuple { entries, ty } => {
+				let found = self.check_annotation(ty.as_deref(), found).as_tuple();
+				let mut position = 0;
+				let mut captured = vec![];
+				let mut rest = None;
+				for entry in entries {
+					let Parse::CaptureTupleEntry { matcher, capture, ty } = entry else { unreachable!("tuple captures only contain tuple capture entries") };
+					let name = match matcher {
+						Some(Token { ty: TokenType::Name { name }, .. }) => name.clone(),
+						Some(_) => {
+							rest = capture.as_deref();
+							continue;
+						},
+						None => {
+							position += 1;
+							(position - 1).to_string()
+						}
+					};
+					let span = match (matcher, capture.as_deref()) {
+						(Some(matcher), _) => matcher.span,
+						(None, Some(Parse::CaptureName { span, .. } | Parse::CaptureView { span, .. })) => *span,
+						_ => self.within
+					};
+					let entry = match found.entry(&name) {
+						Some(entry) => entry,
+						None => {
+							self.errors.push(ErrorInTypes::MissingCaptureEntry { span, name: name.clone(), found: found.clone() });
+							Type::Unknown
+						}
+					};
+					let entry = self.check_annotation(ty.as_deref(), entry);
+					match capture {
+						Some(capture) => self.bind_capture(capture, entry),
+						None => self.bind(&name, span, entry, None)
+					}
+					captured.push(name);
+				}
+				// Mirrors the evaluator - the rest is a tuple with positional entries renumbered from the start, collapsed if
+				// only one positional entry is left.
+				if let Some(capture) = rest {
+					let remaining = match &found {
+						Type::Tuple { entries, rest: found_rest, .. } => {
+							let taken: Vec<usize> = captured.iter().filter_map(|name| positional_index(name)).collect();
+							let mut remaining: Vec<(String, Type)> = entries.iter()
+								.filter(|(name, _)| positional_index(name).is_none() && !captured.contains(name))
+								.cloned()
+								.collect();
+							let mut runs = vec![];
+							Type::push_runs(&mut remaining, &mut runs, found.positional_runs(&taken), 0);
+							Type::tuple(remaining, runs, found_rest.clone())
+						},
+						_ => Type::Unknown
+					};
+					self.bind_capture(capture, remaining);
+				}
+			},
+			// View captures declare the name again where it was viewed from, rather than here.
+			Parse::CaptureView { ty, .. } => {
+				self.check_annotation(ty.as_deref(), found);
+			},
+			_ => unreachable!("captures are always names, tuples or views")
+		}
+	}
+
+	// Annotations take priority over the type that was found, as long as they accept it.
+	fn check_annotation(&mut self, annotation: Option<&Parse>, found: Type) -> Type {
+		let Some(annotation) = annotation else { return found };
+		let expected = self.annotation(annotation);
+		if !expected.accepts(&found) {
+			self.errors.push(ErrorInTypes::Mismatch { span: type_span(annotation), expected: expected.clone(), found });
+		}
+		expected
+	}
+
+	// The type of datum that a capture accepts, without looking at any value.
+	fn capture_type(&mut self, capture: &Parse) -> Type {
+		match capture {
+			Parse::CaptureName { ty: Some(ty), .. } | Parse::CaptureTuple { ty: Some(ty), .. } | Parse::CaptureView { ty: Some(ty), .. } => self.annotation(ty),
+			Parse::CaptureTuple { entries, .. } => {
+				let mut types = vec![];
+				let mut rest = None;
+				let mut position = 0;
+				for entry in entries {
+					let Parse::CaptureTupleEntry { matcher, capture, ty } = entry else { unreachable!("tuple captures only contain tuple capture entries") };
+					let ty = match (ty, capture) {
+						(Some(ty), _) => self.annotation(ty),
+						(None, Some(capture)) => self.capture_type(capture),
+						(None, None) => Type::Unknown
+					};
+					match matcher {
+						Some(Token { ty: TokenType::Name { name }, .. }) => types.push((name.clone(), ty)),
+						// The rest is captured as a tuple, so only a type for all of its entries says anything about them.
+						Some(_) => if let Type::Tuple { entries, runs, rest: Some(entry) } = ty {
+							rest = (entries.is_empty() && runs.is_empty()).then_some(entry);
+						},
+						None => {
+							types.push((position.to_string(), ty));
+							position += 1;
+						}
+					}
+				}
+				Type::tuple(types, vec![], rest)
+			},
+			_ => Type::Unknown
+		}
+	}
+
+	fn annotation(&mut self, ty: &Parse) -> Type {
+		match ty {
+			Parse::TypeName { span, name } => match self.lookup(name) {
+				Some(Binding { denotes: Some(denotes), .. }) => denotes.clone(),
+				// Types captured from elsewhere aren't known until evaluation.
+				Some(Binding { ty: Type::Ty | Type::Unknown, .. }) => Type::Unknown,
+				binding => match Type::builtin(name) {
+					Some(builtin) if binding.is_none() => builtin,
+					_ => {
+						self.not_a_type(*span, name);
+						Type::Unknown
+					}
+				}
+			},
+			Parse::TypeTuple { entries, .. } => {
+				let mut types = vec![];
+				let mut runs = vec![];
+				let mut rest = None;
+				let mut position = 0;
+				for entry in entries {
+					let Parse::TypeTupleEntry { matcher, count, ty } = entry else { unreachable!("tuple types only contain tuple type entries") };
+					let ty = self.annotation(ty);
+					match matcher {
+						Some(Token { ty: TokenType::Name { name }, .. }) => types.push((name.clone(), ty)),
+						Some(_) => rest = Some(Box::new(ty)),
+						// The parser makes sure counts don't take the position past what can be numbered.
+						None => position = Type::push_runs(&mut types, &mut runs, vec![(position, count.map_or(1, |(_, count)| count), ty)], position)
+					}
+				}
+				Type::tuple(types, runs, rest)
+			},
+			Parse::TypeView { ty, .. } => Type::View(Box::new(self.annotation(ty))),
+			capture => self.capture_type(capture)
+		}
+	}
+
+	// Function captures are typed both as a datum and as they're bound, so the same annotation can be looked at twice.
+	fn not_a_type(&mut self, span: Span, name: &str) {
+		if !self.errors.iter().any(|error| matches!(error, ErrorInTypes::NotAType { span: reported, .. } if *reported == span)) {
+			self.errors.push(ErrorInTypes::NotAType { span, name: name.to_string() });
+		}
+	}
+
+	fn infer(&mut self, parse: &Parse) -> Type {
+		match parse {
+			Parse::Block { lets, expr } => {
+				let scope_len = self.scope.len();
+				for parse in lets {
+					self.visit_let(parse);
+				}
+				let ty = self.infer(expr);
+				self.scope.truncate(scope_len);
+				ty
+			},
+			// Throwing doesn't produce a value here, so it fits with anything.
+			Parse::ExprThrow { expr } => {
+				self.infer(expr);
+				Type::Unknown
+			},
+			Parse::ExprChain { first, rest } => {
+				let mut ty = self.infer(first);
+				for parse in rest {
+					let chain_previous = self.chain_previous.replace(ty);
+					ty = self.infer(parse);
+					self.chain_previous = chain_previous;
+				}
+				ty
+			},
+			Parse::ExprAutoChainFirstFnEval { name, span, datum } => {
+				let previous = self.chain_previous.clone().unwrap_or(Type::Unknown);
+				let mut entries = vec![("0".to_string(), previous)];
+				let mut runs = vec![];
+				let datum = match datum.as_deref() {
+					Some(Parse::ValueTuple { entries: datum }) => self.infer_tuple_entries(datum, 1, &mut entries, &mut runs),
+					_ => Some(())
+				};
+				let datum = datum.map_or(Type::Unknown, |_| Type::tuple(entries, runs, None));
+				self.call(name, *span, datum)
+			},
+			Parse::ExprInfix { first, rest } => self.infer_infix(first, rest),
+			Parse::ExprRange { ascending, first, rest } => {
+				let mut previous = self.infer(first).viewed();
+				for (bound, span, operand) in rest {
+					let operand = self.infer(operand).viewed();
+					if !Type::Num.accepts(&previous) || !Type::Num.accepts(&operand) {
+						let bi_op = match (ascending, bound) {
+							(true, Bound::Exclusive) => BiOp::Less,
+							(true, Bound::Inclusive) => BiOp::LessEqual,
+							(false, Bound::Exclusive) => BiOp::More,
+							(false, Bound::Inclusive) => BiOp::MoreEqual
+						};
+						self.errors.push(ErrorInTypes::BiOpOperands { span: *span, bi_op, left: previous, right: operand.clone() });
+					}
+					previous = operand;
+				}
+				Type::Bool
+			},
+			Parse::ExprPrefix { un_ops, term } => {
+				let mut ty = self.infer(term).viewed();
+				for (un_op, span) in un_ops.iter().rev() {
+					ty = match (un_op, ty) {
+						(UnOp::Not, Type::Bool | Type::Unknown) => Type::Bool,
+						(UnOp::Negate | UnOp::DoubleNegate, ty @ (Type::Int | Type::Num | Type::Unknown)) => ty,
+						// Single values can be counted as a tuple holding only that value.
+						(UnOp::Count, _) => Type::Int,
+						(un_op, found) => {
+							self.errors.push(ErrorInTypes::UnOpOperand { span: *span, un_op: un_op.clone(), found });
+							Type::Unknown
+						}
+					};
+				}
+				ty
+			},
+			Parse::ExprAccess { accesses, term } => {
+				let mut ty = self.infer(term).viewed();
+				for (name, span) in accesses {
+					ty = match ty.entry(name) {
+						Some(entry) => entry,
+						None => {
+							self.errors.push(ErrorInTypes::MissingEntry { span: *span, name: name.clone(), found: ty });
+							Type::Unknown
+						}
+					};
+				}
+				ty
+			},
+
+			Parse::ValueFnEval { name, span, datum } => {
+				let datum = self.infer(datum);
+				self.call(name, *span, datum)
+			},
+			Parse::ValueName { name, .. } => match name.as_str() {
+				"true" | "false" => Type::Bool,
+				name => match self.lookup(name) {
+					Some(binding) => binding.ty.clone(),
+					None if Type::builtin(name).is_some() => Type::Ty,
+					None => Type::Unknown
+				}
+			},
+			Parse::ValueChainPrevious => self.chain_previous.clone().unwrap_or(Type::Unknown),
+			Parse::ValueConditional { span, branches, else_expr } => {
+				let mut ty = Some(Type::Unknown);
+				for (if_expr, then_expr) in branches {
+					let condition = self.infer(if_expr).viewed();
+					if !Type::Bool.accepts(&condition) {
+						self.errors.push(ErrorInTypes::ConditionNotBool { span: *span, found: condition });
+					}
+					let branch = self.infer(then_expr);
+					ty = self.unify_branch(*span, ty, branch);
+				}
+				let branch = self.infer(else_expr);
+				self.unify_branch(*span, ty, branch).unwrap_or(Type::Unknown)
+			},
+			// The first attempt that type checks is used.
+			Parse::ValueTry { attempts, fallback } => {
+				let mut ty = None;
+				for attempt in attempts {
+					let errors_len = self.errors.len();
+					let attempt = self.infer(attempt);
+					if self.errors.len() == errors_len {
+						ty = Some(attempt);
+						break;
+					}
+					self.errors.truncate(errors_len);
+				}
+				let fallback = self.infer(fallback);
+				ty.unwrap_or(fallback)
+			},
+			Parse::ValueLoop { capture, initial_expr, body } => {
+				let initial = self.infer(initial_expr);
+				let scope_len = self.scope.len();
+				self.bind_capture(capture, initial);
+				self.infer(body);
+				self.scope.truncate(scope_len);
+				// Loops only finish by throwing, and the types of thrown values aren't followed.
+				Type::Unknown
+			},
+			Parse::ValueBlock { catch, block } => {
+				let ty = self.infer(block);
+				if *catch { Type::Unknown } else { ty }
+			},
+			Parse::ValueFnDef { capture, expr } => {
+				let datum = self.capture_type(capture);
+				let result = self.infer_fn(capture, datum.clone(), expr);
+				Type::Fn { datum: Box::new(datum), result: Box::new(result) }
+			},
+			Parse::ValueTypeDef { .. } => Type::Ty,
+			Parse::ValueNew { ty: annotation, value } => {
+				let ty = self.annotation(annotation);
+				let found = self.infer(value);
+				let expected = match &ty {
+					Type::Defined { definition, .. } => definition.as_ref(),
+					ty => ty
+				};
+				if !expected.accepts(&found) {
+					self.errors.push(ErrorInTypes::Mismatch { span: type_span(annotation), expected: expected.clone(), found });
+				}
+				ty
+			},
+			Parse::ValueRequest { span, ty } => {
+				let ty = self.annotation(ty);
+				self.exchanged.insert(span.index, ty.clone());
+				ty
+			},
+			Parse::ValueProvide { span, value, block } => {
+				let provided = self.infer(value);
+				self.exchanged.insert(span.index, provided);
+				self.infer(block)
+			},
+			Parse::ValueNumber { number: NumberLiteral::Int { .. } } => Type::Int,
+			Parse::ValueNumber { number: NumberLiteral::Num { .. } } => Type::Num,
+			Parse::ValueString { .. } => Type::Str,
+			Parse::ValueTuple { entries } => {
+				let mut types = vec![];
+				let mut runs = vec![];
+				match self.infer_tuple_entries(entries, 0, &mut types, &mut runs) {
+					Some(()) => Type::tuple(types, runs, None),
+					None => Type::Unknown
+				}
+			},
+			Parse::ValueView { name, .. } => Type::View(Box::new(self.lookup(name).map_or(Type::Unknown, |binding| binding.ty.clone()))),
+
+			Parse::Let { .. } | Parse::ValueTupleEntry { .. } => unreachable!("lets and tuple entries are not inferred alone"),
+			Parse::CaptureName { .. } | Parse::CaptureTuple { .. } | Parse::CaptureTupleEntry { .. } | Parse::CaptureView { .. } => unreachable!("captures are not inferred"),
+			Parse::TypeName { .. } | Parse::TypeTuple { .. } | Parse::TypeTupleEntry { .. } | Parse::TypeView { .. } => unreachable!("types are not inferred")
+		}
+	}
+
+	// Gives up on the shape of the tuple if anything of unknown shape is spread into it.
+	fn infer_tuple_entries(&mut self, entries: &[Parse], mut position: usize, types: &mut Vec<(String, Type)>, runs: &mut Vec<(usize, usize, Type)>) -> Option<()> {
+		let mut known = true;
+		// Where each entry named by the tuple itself is, for positional entries that collide with them.
+		let mut named = vec![];
+		for entry in entries {
+			let Parse::ValueTupleEntry { matcher, value } = entry else { unreachable!("tuples only contain tuple entries") };
+			let ty = self.infer(value);
+			match matcher {
+				Some(Token { ty: TokenType::Name { name }, span }) => {
+					named.push((name.clone(), *span));
+					self.push_entry(types, runs, *span, name.clone(), ty);
+				},
+				// Mirrors the evaluator - positional entries are renumbered in order of position, while named entries keep
+				// their names.
+				Some(Token { span, .. }) => match ty.as_tuple() {
+					spread @ Type::Tuple { rest: None, .. } => {
+						for (_, count, ty) in spread.positional_runs(&[]) {
+							self.push_run(types, runs, *span, position, count, ty);
+							position += count;
+						}
+						let Type::Tuple { entries, .. } = spread else { unreachable!("matched as a tuple above") };
+						for (name, ty) in entries {
+							if positional_index(&name).is_none() {
+								self.push_entry(types, runs, *span, name, ty);
+							}
+						}
+					},
+					_ => known = false
+				},
+				// Positions before this one are already taken, so only entries named by the tuple can collide with it.
+				None => {
+					let name = position.to_string();
+					match named.iter().find(|(named, _)| *named == name) {
+						Some((_, span)) => self.errors.push(ErrorInTypes::DuplicateEntry { span: *span, name }),
+						None => types.push((name, ty))
+					}
+					position += 1;
+				}
+			}
+		}
+		known.then_some(())
+	}
+
+	// Entries spread into a tuple point at the spread they came from.
+	fn push_entry(&mut self, types: &mut Vec<(String, Type)>, runs: &[(usize, usize, Type)], span: Span, name: String, ty: Type) {
+		if own_entry(types, runs, &name).is_some() {
+			self.errors.push(ErrorInTypes::DuplicateEntry { span, name });
+		} else {
+			types.push((name, ty));
+		}
+	}
+
+	// Runs of one are pushed as single entries.
+	fn push_run(&mut self, types: &mut Vec<(String, Type)>, runs: &mut Vec<(usize, usize, Type)>, span: Span, start: usize, count: usize, ty: Type) {
+		let range = start..start + count;
+		match types.iter().find(|(name, _)| positional_index(name).is_some_and(|index| range.contains(&index))) {
+			Some((name, _)) => self.errors.push(ErrorInTypes::DuplicateEntry { span, name: name.clone() }),
+			None if count == 1 => types.push((start.to_string(), ty)),
+			None => runs.push((start, count, ty))
+		}
+	}
+
+	fn unify_branch(&mut self, span: Span, ty: Option<Type>, branch: Type) -> Option<Type> {
+		let ty = ty?;
+		let unified = ty.unify(&branch);
+		if unified.is_none() {
+			self.errors.push(ErrorInTypes::IncompatibleBranches { span, first: ty, second: branch });
+		}
+		unified
+	}
+
+	fn call(&mut self, name: &str, span: Span, datum: Type) -> Type {
+		let Some(binding) = self.lookup(name) else { return Type::Unknown };
+		match binding.ty.clone() {
+			Type::Fn { datum: expected, result } => {
+				if !expected.accepts(&datum) {
+					self.errors.push(ErrorInTypes::DatumMismatch { span, name: name.to_string(), expected: *expected, found: datum });
+				}
+				*result
+			},
+			Type::Unknown => Type::Unknown,
+			found => {
+				self.errors.push(ErrorInTypes::NotAFunction { span, name: name.to_string(), found });
+				Type::Unknown
+			}
+		}
+	}
+
+	// Mirrors the evaluator - operators of the same priority are applied left to right.
+	fn infer_infix(&mut self, first: &Parse, rest: &[(BiOp, Span, Parse)]) -> Type {
+		let mut left = self.infer(first).viewed();
+		for (bi_op, span, operand) in rest {
+			let right = self.infer(operand).viewed();
+			left = match bi_op {
+				BiOp::And | BiOp::Or => {
+					if !Type::Bool.accepts(&left) || !Type::Bool.accepts(&right) {
+						self.errors.push(ErrorInTypes::BiOpOperands { span: *span, bi_op: bi_op.clone(), left, right });
+					}
+					Type::Bool
+				},
+				BiOp::Equal | BiOp::NotEqual => Type::Bool,
+				BiOp::Less | BiOp::More | BiOp::LessEqual | BiOp::MoreEqual => unreachable!("comparisons are always ranges"),
+				bi_op => match (bi_op, &left, &right) {
+					(_, Type::Int | Type::Num | Type::Unknown, Type::Int | Type::Num | Type::Unknown) => match bi_op {
+						BiOp::Divide => Type::Num,
+						BiOp::FloorDivide | BiOp::CeilDivide => Type::Int,
+						_ if left == Type::Unknown || right == Type::Unknown => Type::Unknown,
+						_ if left == Type::Int && right == Type::Int => Type::Int,
+						_ => Type::Num
+					},
+					_ => {
+						self.errors.push(ErrorInTypes::BiOpOperands { span: *span, bi_op: bi_op.clone(), left, right });
+						Type::Unknown
+					}
+				}
+			};
+		}
+		left
+	}
+}
+
+fn type_span(ty: &Parse) -> Span {
+	match ty {
+		Parse::TypeName { span, .. } | Parse::TypeTuple { span, .. } | Parse::TypeView { span, .. } => *span,
+		_ => unreachable!("annotations are always types")
+	}
+}
+
+fn untyped_names(capture: &Parse, untyped: &mut Vec<(Span, String)>) {
+	match capture {
+		Parse::CaptureName { span, name, ty: None } => untyped.push((*span, name.clone())),
+		Parse::CaptureTuple { entries, ty: None } => {
+			for entry in entries {
+				match entry {
+					Parse::CaptureTupleEntry { capture: Some(capture), .. } => untyped_names(capture, untyped),
+					Parse::CaptureTupleEntry { matcher: Some(Token { ty: TokenType::Name { name }, span }), capture: None, ty: None } => {
+						untyped.push((*span, name.clone()));
+					},
+					_ => {}
+				}
+			}
+		},
+		_ => {}
+	}
+}
\ No newline at end of file
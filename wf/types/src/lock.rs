@@ -0,0 +1,80 @@
+// Lockfiles.
+//
+// A lockfile records the inferred signature of every top-level let, one per
+// line, written like the let that declares it. Checking against a lockfile
+// points out declarations whose inferred types have drifted since it was
+// written, so that changes to them are always deliberate.
+
+use std::fmt;
+
+use wf_token::{Span, TokenType, Tokeniser};
+
+use crate::{ty::written_name, Signature, Type};
+
+#[derive(Debug, Clone)]
+pub enum ErrorInLock {
+	Drifted { span: Span, name: String, locked: String, inferred: String },
+	Unlocked { span: Span, name: String, inferred: String },
+	NoLongerDeclared { name: String, locked: String },
+	// Lines are numbered from 1, like spans.
+	MalformedLine { line: usize }
+}
+
+impl fmt::Display for Signature {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match &self.denotes {
+			Some(Type::Defined { definition, .. }) => write!(f, "let {} = ty {definition}", written_name(&self.name)),
+			_ => write!(f, "let {} : {}", written_name(&self.name), self.ty)
+		}
+	}
+}
+
+// Read with the tokeniser, so that backticked names come out the same as they do in source files.
+fn locked_name(line: &str) -> Option<String> {
+	let mut tokens = Tokeniser::new(line.bytes()).filter(|token| !matches!(token.ty, TokenType::Whitespace));
+	match (tokens.next()?.ty, tokens.next()?.ty) {
+		(TokenType::Let, TokenType::Name { name }) => Some(name),
+		_ => None
+	}
+}
+
+pub fn write_lock(signatures: &[Signature]) -> String {
+	let mut lock = String::from("-- inferred by `wf lock`, change the source file instead of editing this\n");
+	for signature in signatures {
+		lock += &format!("{signature}\n");
+	}
+	lock
+}
+
+pub fn check_lock(lock: &str, signatures: &[Signature]) -> Result<(), Vec<ErrorInLock>> {
+	let mut errors = vec![];
+	let mut locked = vec![];
+	for (index, line) in lock.lines().enumerate() {
+		let line = line.trim();
+		if line.is_empty() || line.starts_with("--") {
+			continue;
+		}
+		match locked_name(line) {
+			Some(name) => locked.push((name, line)),
+			None => errors.push(ErrorInLock::MalformedLine { line: index + 1 })
+		}
+	}
+	for signature in signatures {
+		let inferred = signature.to_string();
+		match locked.iter().find(|(name, _)| *name == signature.name) {
+			Some((_, line)) if *line == inferred => {},
+			Some((_, line)) => errors.push(ErrorInLock::Drifted { span: signature.span, name: signature.name.clone(), locked: line.to_string(), inferred }),
+			None => errors.push(ErrorInLock::Unlocked { span: signature.span, name: signature.name.clone(), inferred })
+		}
+	}
+	for (name, line) in locked {
+		if signatures.iter().all(|signature| signature.name != name) {
+			errors.push(ErrorInLock::NoLongerDeclared { name: name.to_string(), locked: line.to_string() });
+		}
+	}
+	if errors.is_empty() {
+		Ok(())
+	} else {
+		Err(errors)
+	}
+}
\ No newline at end of file
@@ -0,0 +1,234 @@
+use std::{fmt, ops::Range};
+
+use wf_parse::positional_index;
+use wf_token::{Token, TokenType, Tokeniser};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+	Int,
+	Num,
+	Bool,
+	Str,
+	// The type of type definitions.
+	Ty,
+	// `rest` is the type of any entries beyond the named ones. Counted entries of tuple types are kept as runs of
+	// `(start, count, type)` in order of position, rather than as one entry per position, as counts can be very large.
+	Tuple { entries: Vec<(String, Type)>, runs: Vec<(usize, usize, Type)>, rest: Option<Box<Type>> },
+	Fn { datum: Box<Type>, result: Box<Type> },
+	// Every `ty` definition is distinct, even from others written the same way.
+	Defined { id: usize, name: String, definition: Box<Type> },
+	View(Box<Type>),
+	// Not enough is known locally to decide the type, so it's not checked.
+	Unknown
+}
+
+impl Type {
+	pub fn builtin(name: &str) -> Option<Self> {
+		match name {
+			"int" => Some(Type::Int),
+			"num" => Some(Type::Num),
+			"bool" => Some(Type::Bool),
+			"str" => Some(Type::Str),
+			"ty" => Some(Type::Ty),
+			_ => None
+		}
+	}
+
+	// Tuples holding a single positional entry are the same as that entry, unless the entry is a tuple itself.
+	pub fn tuple(mut entries: Vec<(String, Type)>, runs: Vec<(usize, usize, Type)>, rest: Option<Box<Type>>) -> Type {
+		if rest.is_none() && runs.is_empty() && entries.len() == 1 && entries[0].0 == "0" && !matches!(entries[0].1, Type::Tuple { .. }) {
+			entries.remove(0).1
+		} else {
+			Type::Tuple { entries, runs, rest }
+		}
+	}
+
+	// A single value can be captured as a tuple holding only that value.
+	pub fn as_tuple(&self) -> Type {
+		match self {
+			Type::Tuple { .. } | Type::Unknown => self.clone(),
+			ty => Type::Tuple { entries: vec![("0".to_string(), ty.clone())], runs: vec![], rest: None }
+		}
+	}
+
+	// The positional entries of a tuple in order of position, as runs of `(start, count, type)` where single entries are
+	// runs of one. Positions in `taken` are left out, splitting any runs they fall inside.
+	pub fn positional_runs(&self, taken: &[usize]) -> Vec<(usize, usize, Type)> {
+		let Type::Tuple { entries, runs, .. } = self else { return vec![] };
+		let mut positional: Vec<(usize, usize, Type)> = entries.iter()
+			.filter_map(|(name, ty)| positional_index(name).map(|index| (index, 1, ty.clone())))
+			.chain(runs.iter().cloned())
+			.collect();
+		positional.sort_by_key(|(start, _, _)| *start);
+		let mut split = vec![];
+		for (mut start, count, ty) in positional {
+			let end = start + count;
+			let mut inside: Vec<usize> = taken.iter().copied().filter(|index| (start..end).contains(index)).collect();
+			inside.sort();
+			inside.push(end);
+			for index in inside {
+				if index > start {
+					split.push((start, index - start, ty.clone()));
+				}
+				start = index + 1;
+			}
+		}
+		split
+	}
+
+	// Numbers runs of positional entries in order from `position`, adding them to the entries of a tuple being built.
+	// Runs of one are added as single entries. Returns the position after the last run.
+	pub fn push_runs(entries: &mut Vec<(String, Type)>, runs: &mut Vec<(usize, usize, Type)>, positional: Vec<(usize, usize, Type)>, mut position: usize) -> usize {
+		for (_, count, ty) in positional {
+			if count == 1 {
+				entries.push((position.to_string(), ty));
+			} else {
+				runs.push((position, count, ty));
+			}
+			position += count;
+		}
+		position
+	}
+
+	pub fn entry(&self, name: &str) -> Option<Type> {
+		match self {
+			Type::Tuple { entries, runs, rest } => own_entry(entries, runs, name).or(rest.as_deref()).cloned(),
+			// Values of defined types can be accessed like the tuples they were created from.
+			Type::Defined { definition, .. } => definition.entry(name),
+			Type::Unknown => Some(Type::Unknown),
+			// Single values hold themselves as their first positional entry.
+			ty if name == "0" => Some(ty.clone()),
+			_ => None
+		}
+	}
+
+	// Views are read through when their value is used, such as by operators.
+	pub fn viewed(self) -> Type {
+		match self {
+			Type::View(ty) => *ty,
+			ty => ty
+		}
+	}
+
+	// Whether a value of the found type can be used where this type is expected.
+	pub fn accepts(&self, found: &Type) -> bool {
+		match (self, found) {
+			(Type::Unknown, _) | (_, Type::Unknown) => true,
+			(Type::Num, Type::Int) => true,
+			(Type::Tuple { entries, runs, rest }, Type::Tuple { entries: found_entries, runs: found_runs, rest: found_rest }) => {
+				let expected_found = entries.iter().all(|(name, ty)| {
+					match own_entry(found_entries, found_runs, name).or(found_rest.as_deref()) {
+						Some(found) => ty.accepts(found),
+						None => false
+					}
+				});
+				// Runs are matched a range of positions at a time, counting how many of them the found tuple has.
+				let runs_found = runs.iter().all(|(start, count, ty)| {
+					let range = *start..start + count;
+					let inside = positional_inside(found_entries, found_runs, &range);
+					inside.iter().all(|(_, found)| ty.accepts(found))
+						&& (inside.iter().map(|(covered, _)| covered).sum::<usize>() == *count || found_rest.as_ref().is_some_and(|found_rest| ty.accepts(found_rest)))
+				});
+				let rest_found = rest.as_ref().is_none_or(|rest| {
+					let entries_fit = found_entries.iter()
+						.filter(|(found_name, _)| own_entry(entries, runs, found_name).is_none())
+						.all(|(_, found)| rest.accepts(found));
+					let runs_fit = found_runs.iter().all(|(start, count, found)| {
+						let covered: usize = positional_inside(entries, runs, &(*start..start + count)).iter().map(|(covered, _)| covered).sum();
+						covered == *count || rest.accepts(found)
+					});
+					entries_fit && runs_fit
+				});
+				expected_found && runs_found && rest_found
+			},
+			(Type::Tuple { .. }, found) if !matches!(found, Type::Tuple { .. }) => self.accepts(&found.as_tuple()),
+			(Type::Fn { datum, result }, Type::Fn { datum: found_datum, result: found_result }) => {
+				found_datum.accepts(datum) && result.accepts(found_result)
+			},
+			(Type::Defined { id, .. }, Type::Defined { id: found_id, .. }) => id == found_id,
+			(Type::View(ty), Type::View(found)) => ty.accepts(found),
+			(expected, found) => expected == found
+		}
+	}
+
+	// The type which both types can be used as, if there is one.
+	pub fn unify(&self, other: &Type) -> Option<Type> {
+		match (self, other) {
+			(Type::Unknown, ty) | (ty, Type::Unknown) => Some(ty.clone()),
+			(ty, other) if ty.accepts(other) => Some(ty.clone()),
+			(ty, other) if other.accepts(ty) => Some(other.clone()),
+			_ => None
+		}
+	}
+}
+
+impl fmt::Display for Type {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Type::Int => write!(f, "int"),
+			Type::Num => write!(f, "num"),
+			Type::Bool => write!(f, "bool"),
+			Type::Str => write!(f, "str"),
+			Type::Ty => write!(f, "ty"),
+			Type::Tuple { entries, runs, rest } => {
+				let mut written = vec![];
+				let mut position = 0;
+				let mut runs = runs.iter().peekable();
+				for (name, ty) in entries {
+					// Runs are written where their positions come, before the positional entries after them.
+					while positional_index(name).is_some() && let Some((_, count, run)) = runs.next_if(|(start, _, _)| *start == position) {
+						written.push(format!("{count} {run}"));
+						position += count;
+					}
+					if *name == position.to_string() {
+						position += 1;
+						written.push(ty.to_string());
+					} else {
+						// Entry names can also be positions, which aren't names to the tokeniser.
+						let name = if positional_index(name).is_some() { name.clone() } else { written_name(name) };
+						written.push(format!(".{name} {ty}"));
+					}
+				}
+				for (_, count, run) in runs {
+					written.push(format!("{count} {run}"));
+				}
+				if let Some(rest) = rest {
+					written.push(format!("... {rest}"));
+				}
+				write!(f, "[{}]", written.join(", "))
+			},
+			Type::Fn { datum, result } => write!(f, "fn {datum} {result}"),
+			Type::Defined { name, .. } => write!(f, "{}", written_name(name)),
+			Type::View(ty) => write!(f, "{{{ty}}}"),
+			Type::Unknown => write!(f, "unknown")
+		}
+	}
+}
+
+// Names are backticked unless the tokeniser would read them back as the same name without.
+pub(crate) fn written_name(name: &str) -> String {
+	let mut tokens = Tokeniser::new(name.bytes());
+	match (tokens.next(), tokens.next()) {
+		(Some(Token { ty: TokenType::Name { name: read }, .. }), None) if read == name => name.to_string(),
+		_ => format!("`{name}`")
+	}
+}
+
+// Looks for an entry among a tuple's own entries and runs, not including the rest of the tuple.
+pub(crate) fn own_entry<'a>(entries: &'a [(String, Type)], runs: &'a [(usize, usize, Type)], name: &str) -> Option<&'a Type> {
+	entries.iter().find(|(entry_name, _)| entry_name == name).map(|(_, ty)| ty).or_else(|| {
+		let index = positional_index(name)?;
+		runs.iter().find(|(start, count, _)| (*start..start + count).contains(&index)).map(|(_, _, ty)| ty)
+	})
+}
+
+// The positional entries and runs of a tuple that fall inside a range of positions, with how many positions each covers.
+fn positional_inside<'a>(entries: &'a [(String, Type)], runs: &'a [(usize, usize, Type)], range: &Range<usize>) -> Vec<(usize, &'a Type)> {
+	let entries = entries.iter()
+		.filter(|(name, _)| positional_index(name).is_some_and(|index| range.contains(&index)))
+		.map(|(_, ty)| (1, ty));
+	let runs = runs.iter()
+		.map(|(start, count, ty)| ((start + count).min(range.end).saturating_sub(*start.max(&range.start)), ty))
+		.filter(|(covered, _)| *covered > 0);
+	entries.chain(runs).collect()
+}
\ No newline at end of file